@@ -0,0 +1,100 @@
+use std::collections::LinkedList;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use model::{Journal, Page};
+
+const PAGES: usize = 10_000;
+const MIDDLE: usize = PAGES / 2;
+
+/// The page store `Journal` used before it switched to a vector of boxed pages.
+struct LinkedListJournal {
+    pages: LinkedList<Page>,
+}
+
+impl LinkedListJournal {
+    fn new(pages: usize) -> Self {
+        LinkedListJournal {
+            pages: (0..pages).map(|_| Page::default()).collect(),
+        }
+    }
+
+    fn insert(&mut self, at: usize, page: Page) {
+        let mut tail = self.pages.split_off(at);
+        self.pages.push_back(page);
+        self.pages.append(&mut tail);
+    }
+
+    fn remove(&mut self, at: usize) -> Option<Page> {
+        let mut tail = self.pages.split_off(at);
+        let page = tail.pop_front();
+        self.pages.append(&mut tail);
+        page
+    }
+
+    fn get(&self, index: usize) -> Option<&Page> {
+        self.pages.iter().nth(index)
+    }
+}
+
+fn journal(pages: usize) -> Journal {
+    let mut journal = Journal::default();
+    for _ in 1..pages {
+        journal.push_back(Page::default());
+    }
+    journal
+}
+
+fn insert_and_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert and remove in the middle of 10k pages");
+    let mut linked_list = LinkedListJournal::new(PAGES);
+    group.bench_function("linked list", |b| {
+        b.iter(|| {
+            let page = linked_list.remove(black_box(MIDDLE)).unwrap();
+            linked_list.insert(black_box(MIDDLE), page);
+        })
+    });
+    let mut journal = journal(PAGES);
+    group.bench_function("vector of boxes", |b| {
+        b.iter(|| {
+            let page = journal.remove(black_box(MIDDLE)).unwrap();
+            journal.insert(black_box(MIDDLE), page);
+        })
+    });
+    group.finish();
+}
+
+fn random_access(c: &mut Criterion) {
+    let mut group = c.benchmark_group("access the middle of 10k pages");
+    let linked_list = LinkedListJournal::new(PAGES);
+    group.bench_function("linked list", |b| {
+        b.iter(|| linked_list.get(black_box(MIDDLE)).unwrap().id())
+    });
+    let journal = journal(PAGES);
+    group.bench_function("vector of boxes", |b| {
+        b.iter(|| journal.get(black_box(MIDDLE)).unwrap().id())
+    });
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate over 10k pages");
+    let linked_list = LinkedListJournal::new(PAGES);
+    group.bench_function("linked list", |b| {
+        b.iter(|| {
+            linked_list
+                .pages
+                .iter()
+                .map(|page| page.len())
+                .sum::<usize>()
+        })
+    });
+    let journal = journal(PAGES);
+    group.bench_function("vector of boxes", |b| {
+        b.iter(|| journal.iter().map(|page| page.len()).sum::<usize>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, insert_and_remove, random_access, iterate);
+criterion_main!(benches);
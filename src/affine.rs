@@ -0,0 +1,163 @@
+use crate::{FloatPoint, Point};
+
+/// A two dimensional affine transformation.
+///
+/// A point `(x, y)` is mapped to `(a * x + c * y + e, b * x + d * y + f)`. Results of `apply`
+/// are rounded to the nearest integer coordinate; use `apply_float` to keep sub-unit precision.
+/// Lines and rectangles transform with `apply_float`, so chained transformations do not round
+/// after every step.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Affine {
+    pub fn identity() -> Self {
+        Affine {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn translate(dx: f64, dy: f64) -> Self {
+        Affine {
+            e: dx,
+            f: dy,
+            ..Self::identity()
+        }
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Affine {
+            a: sx,
+            d: sy,
+            ..Self::identity()
+        }
+    }
+
+    /// A counterclockwise rotation around the origin.
+    pub fn rotate(radians: f64) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Affine {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::identity()
+        }
+    }
+
+    pub fn skew(x_radians: f64, y_radians: f64) -> Self {
+        Affine {
+            b: y_radians.tan(),
+            c: x_radians.tan(),
+            ..Self::identity()
+        }
+    }
+
+    /// Returns the transformation that applies `self` first and `next` afterwards.
+    pub fn then(&self, next: &Affine) -> Self {
+        Affine {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// Whether the transformation only moves points without scaling, rotating or skewing.
+    pub fn is_translation(&self) -> bool {
+        self.a == 1.0 && self.b == 0.0 && self.c == 0.0 && self.d == 1.0
+    }
+
+    /// Whether axis aligned rectangles stay axis aligned under this transformation.
+    pub fn preserves_axes(&self) -> bool {
+        (self.b == 0.0 && self.c == 0.0) || (self.a == 0.0 && self.d == 0.0)
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        self.apply_float(point.into()).round()
+    }
+
+    /// Transforms a point without rounding to model units.
+    pub fn apply_float(&self, point: FloatPoint) -> FloatPoint {
+        FloatPoint {
+            x: self.a * point.x + self.c * point.y + self.e,
+            y: self.b * point.x + self.d * point.y + self.f,
+        }
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn identity_keeps_points() {
+        assert_eq!(Affine::identity().apply((3, -4).into()), (3, -4));
+    }
+
+    #[test]
+    fn translates() {
+        assert_eq!(Affine::translate(2.0, 3.0).apply((1, 1).into()), (3, 4));
+    }
+
+    #[test]
+    fn scales() {
+        assert_eq!(Affine::scale(2.0, 0.5).apply((3, 4).into()), (6, 2));
+    }
+
+    #[test]
+    fn rotates_counterclockwise() {
+        assert_eq!(Affine::rotate(FRAC_PI_2).apply((10, 0).into()), (0, 10));
+    }
+
+    #[test]
+    fn skews() {
+        let skew = Affine::skew(std::f64::consts::FRAC_PI_4, 0.0);
+        assert_eq!(skew.apply((0, 10).into()), (10, 10));
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        let affine = Affine::scale(2.0, 2.0).then(&Affine::translate(1.0, 0.0));
+        assert_eq!(affine.apply((1, 1).into()), (3, 2));
+        let affine = Affine::translate(1.0, 0.0).then(&Affine::scale(2.0, 2.0));
+        assert_eq!(affine.apply((1, 1).into()), (4, 2));
+    }
+
+    #[test]
+    fn detects_axis_preserving_transformations() {
+        assert!(Affine::scale(2.0, -1.0).preserves_axes());
+        assert!(!Affine::rotate(0.3).preserves_axes());
+        assert!(!Affine::skew(0.3, 0.0).preserves_axes());
+    }
+
+    #[test]
+    fn apply_float_keeps_sub_unit_precision() {
+        let affine = Affine::scale(0.5, 0.5);
+        assert_eq!(
+            affine.apply_float(FloatPoint::new(3.0, 1.0)),
+            FloatPoint::new(1.5, 0.5)
+        );
+        assert_eq!(affine.apply((3, 1).into()), (2, 1));
+    }
+}
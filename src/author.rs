@@ -0,0 +1,22 @@
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash)]
+pub struct AuthorId(String);
+
+impl AuthorId {
+    pub fn new(id: impl Into<String>) -> Self {
+        AuthorId(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_id() {
+        assert_eq!(AuthorId::new("alice").as_str(), "alice");
+    }
+}
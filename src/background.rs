@@ -0,0 +1,360 @@
+use std::f64::consts::FRAC_PI_2;
+
+use rstar::AABB;
+
+use crate::Point;
+
+/// A procedurally generated page background.
+///
+/// Patterns are anchored at the origin of the page, so any viewport yields the same marks where
+/// it overlaps another. All lengths are in model coordinates.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub enum Background {
+    #[default]
+    Plain,
+    /// Horizontal lines, with an optional vertical margin line at the given x coordinate.
+    Ruled {
+        spacing: i128,
+        margin: Option<i128>,
+    },
+    Grid {
+        spacing: i128,
+    },
+    Dots {
+        spacing: i128,
+    },
+    /// A grid of equilateral triangles with the given side length.
+    Isometric {
+        spacing: i128,
+    },
+    /// Ruled lines with a cue column on the left and a summary area at the bottom of the page.
+    ///
+    /// The summary area is only drawn on pages with a fixed size.
+    Cornell {
+        spacing: i128,
+        cue_width: i128,
+        summary_height: i128,
+    },
+    /// Staves of five lines, separated by `gap`.
+    MusicStaff {
+        spacing: i128,
+        gap: i128,
+    },
+}
+
+/// A part of a background to be drawn.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum BackgroundMark {
+    Line(Point, Point),
+    Dot(Point),
+}
+
+impl Background {
+    /// Generates the marks that are visible in the viewport.
+    ///
+    /// Pass the page bounds for pages with a fixed size; marks are clipped to them.
+    pub fn marks(&self, viewport: AABB<Point>, page: Option<AABB<Point>>) -> Vec<BackgroundMark> {
+        let Some(area) = Area::new(viewport, page) else {
+            return Vec::new();
+        };
+        match *self {
+            Background::Plain => Vec::new(),
+            Background::Ruled { spacing, margin } => {
+                let mut marks = area.family(FRAC_PI_2, spacing as f64);
+                marks.extend(margin.and_then(|x| area.vertical(x, area.max_y)));
+                marks
+            }
+            Background::Grid { spacing } => {
+                let mut marks = area.family(FRAC_PI_2, spacing as f64);
+                marks.extend(area.family(0.0, spacing as f64));
+                marks
+            }
+            Background::Dots { spacing } => area.dots(spacing),
+            Background::Isometric { spacing } => {
+                let height = spacing as f64 * 3f64.sqrt() / 2.0;
+                [FRAC_PI_2, FRAC_PI_2 / 3.0, 5.0 * FRAC_PI_2 / 3.0]
+                    .into_iter()
+                    .flat_map(|angle| area.family(angle, height))
+                    .collect()
+            }
+            Background::Cornell {
+                spacing,
+                cue_width,
+                summary_height,
+            } => {
+                let summary = page.map(|page| (page.upper().y - summary_height) as f64);
+                let mut marks: Vec<BackgroundMark> = area
+                    .family(FRAC_PI_2, spacing as f64)
+                    .into_iter()
+                    .filter(|mark| match (mark, summary) {
+                        (BackgroundMark::Line(start, _), Some(summary)) => {
+                            (start.y as f64) < summary
+                        }
+                        _ => true,
+                    })
+                    .collect();
+                let bottom = summary.unwrap_or(area.max_y).min(area.max_y);
+                marks.extend(area.vertical(cue_width, bottom));
+                if let Some(summary) = summary {
+                    marks.extend(area.horizontal(summary));
+                }
+                marks
+            }
+            Background::MusicStaff { spacing, gap } => {
+                let period = (4 * spacing + gap) as f64;
+                if period <= 0.0 || spacing <= 0 {
+                    return Vec::new();
+                }
+                let first = (area.min_y / period).floor() as i128;
+                let last = (area.max_y / period).ceil() as i128;
+                (first..=last)
+                    .flat_map(|staff| {
+                        (0..5).map(move |line| staff as f64 * period + (line * spacing) as f64)
+                    })
+                    .filter_map(|y| area.horizontal(y))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The part of the page that is visible in the viewport.
+struct Area {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+fn point(x: f64, y: f64) -> Point {
+    Point::new(x.round() as i128, y.round() as i128)
+}
+
+impl Area {
+    fn new(viewport: AABB<Point>, page: Option<AABB<Point>>) -> Option<Self> {
+        let (mut lower, mut upper) = (viewport.lower(), viewport.upper());
+        if let Some(page) = page {
+            lower = Point::new(lower.x.max(page.lower().x), lower.y.max(page.lower().y));
+            upper = Point::new(upper.x.min(page.upper().x), upper.y.min(page.upper().y));
+        }
+        (lower.x <= upper.x && lower.y <= upper.y).then_some(Area {
+            min_x: lower.x as f64,
+            min_y: lower.y as f64,
+            max_x: upper.x as f64,
+            max_y: upper.y as f64,
+        })
+    }
+
+    fn horizontal(&self, y: f64) -> Option<BackgroundMark> {
+        (self.min_y..=self.max_y)
+            .contains(&y)
+            .then(|| BackgroundMark::Line(point(self.min_x, y), point(self.max_x, y)))
+    }
+
+    /// A vertical line from the top of the area down to `bottom`.
+    fn vertical(&self, x: i128, bottom: f64) -> Option<BackgroundMark> {
+        let x = x as f64;
+        ((self.min_x..=self.max_x).contains(&x) && self.min_y <= bottom)
+            .then(|| BackgroundMark::Line(point(x, self.min_y), point(x, bottom)))
+    }
+
+    /// Parallel lines whose normal has the given angle, `spacing` apart, clipped to the area.
+    fn family(&self, angle: f64, spacing: f64) -> Vec<BackgroundMark> {
+        if spacing <= 0.0 {
+            return Vec::new();
+        }
+        let (ny, nx) = angle.sin_cos();
+        let (dx, dy) = (-ny, nx);
+        let offsets = [
+            (self.min_x, self.min_y),
+            (self.max_x, self.min_y),
+            (self.min_x, self.max_y),
+            (self.max_x, self.max_y),
+        ]
+        .map(|(x, y)| x * nx + y * ny);
+        let lowest = offsets.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = offsets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let first = (lowest / spacing).ceil() as i128;
+        let last = (highest / spacing).floor() as i128;
+        (first..=last)
+            .filter_map(|k| {
+                let offset = k as f64 * spacing;
+                self.clip((offset * nx, offset * ny), (dx, dy))
+            })
+            .collect()
+    }
+
+    /// Clips the infinite line through `origin` along `direction` to the area.
+    fn clip(&self, origin: (f64, f64), direction: (f64, f64)) -> Option<BackgroundMark> {
+        let mut enter = f64::NEG_INFINITY;
+        let mut exit = f64::INFINITY;
+        for (start, delta, min, max) in [
+            (origin.0, direction.0, self.min_x, self.max_x),
+            (origin.1, direction.1, self.min_y, self.max_y),
+        ] {
+            if delta.abs() < 1e-12 {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((min - start) / delta, (max - start) / delta);
+            enter = enter.max(t0.min(t1));
+            exit = exit.min(t0.max(t1));
+        }
+        if enter > exit {
+            return None;
+        }
+        let start = point(
+            origin.0 + enter * direction.0,
+            origin.1 + enter * direction.1,
+        );
+        let end = point(origin.0 + exit * direction.0, origin.1 + exit * direction.1);
+        // Lines run downwards, or to the right if they are horizontal.
+        Some(if (start.y, start.x) <= (end.y, end.x) {
+            BackgroundMark::Line(start, end)
+        } else {
+            BackgroundMark::Line(end, start)
+        })
+    }
+
+    fn dots(&self, spacing: i128) -> Vec<BackgroundMark> {
+        if spacing <= 0 {
+            return Vec::new();
+        }
+        let range = |min: f64, max: f64| {
+            (min / spacing as f64).ceil() as i128..=(max / spacing as f64).floor() as i128
+        };
+        range(self.min_y, self.max_y)
+            .flat_map(|row| {
+                range(self.min_x, self.max_x).map(move |column| {
+                    BackgroundMark::Dot(Point::new(column * spacing, row * spacing))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport(lower: (i32, i32), upper: (i32, i32)) -> AABB<Point> {
+        AABB::from_corners(lower.into(), upper.into())
+    }
+
+    fn line(start: (i32, i32), end: (i32, i32)) -> BackgroundMark {
+        BackgroundMark::Line(start.into(), end.into())
+    }
+
+    #[test]
+    fn plain_has_no_marks() {
+        assert!(Background::Plain
+            .marks(viewport((0, 0), (100, 100)), None)
+            .is_empty());
+    }
+
+    #[test]
+    fn ruled_lines_span_viewport() {
+        let ruled = Background::Ruled {
+            spacing: 10,
+            margin: Some(15),
+        };
+        let marks = ruled.marks(viewport((0, 5), (30, 25)), None);
+        assert_eq!(
+            marks,
+            vec![
+                line((0, 10), (30, 10)),
+                line((0, 20), (30, 20)),
+                line((15, 5), (15, 25))
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_are_clipped_to_page() {
+        let grid = Background::Grid { spacing: 10 };
+        let marks = grid.marks(
+            viewport((-20, -20), (15, 15)),
+            Some(viewport((0, 0), (100, 100))),
+        );
+        assert_eq!(
+            marks,
+            vec![
+                line((0, 0), (15, 0)),
+                line((0, 10), (15, 10)),
+                line((0, 0), (0, 15)),
+                line((10, 0), (10, 15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dots_are_placed_on_grid() {
+        let marks = Background::Dots { spacing: 10 }.marks(viewport((5, 5), (25, 15)), None);
+        assert_eq!(
+            marks,
+            vec![
+                BackgroundMark::Dot((10, 10).into()),
+                BackgroundMark::Dot((20, 10).into())
+            ]
+        );
+    }
+
+    #[test]
+    fn isometric_has_three_directions() {
+        let marks =
+            Background::Isometric { spacing: 100 }.marks(viewport((0, 0), (200, 200)), None);
+        let slopes: Vec<i128> = marks
+            .iter()
+            .filter_map(|mark| match mark {
+                BackgroundMark::Line(start, end) if start.x != end.x => {
+                    Some((end.y - start.y) * 100 / (end.x - start.x))
+                }
+                _ => None,
+            })
+            .collect();
+        assert!(slopes.contains(&0));
+        assert!(slopes.iter().any(|slope| (170..=174).contains(slope)));
+        assert!(slopes.iter().any(|slope| (-174..=-170).contains(slope)));
+    }
+
+    #[test]
+    fn cornell_has_cue_column_and_summary() {
+        let cornell = Background::Cornell {
+            spacing: 10,
+            cue_width: 20,
+            summary_height: 25,
+        };
+        let page = viewport((0, 0), (50, 50));
+        let marks = cornell.marks(page, Some(page));
+        assert_eq!(
+            marks,
+            vec![
+                line((0, 0), (50, 0)),
+                line((0, 10), (50, 10)),
+                line((0, 20), (50, 20)),
+                line((20, 0), (20, 25)),
+                line((0, 25), (50, 25)),
+            ]
+        );
+    }
+
+    #[test]
+    fn music_staff_groups_five_lines() {
+        let staff = Background::MusicStaff {
+            spacing: 2,
+            gap: 12,
+        };
+        let ys: Vec<i128> = staff
+            .marks(viewport((0, 0), (10, 30)), None)
+            .iter()
+            .filter_map(|mark| match mark {
+                BackgroundMark::Line(start, _) => Some(start.y),
+                BackgroundMark::Dot(_) => None,
+            })
+            .collect();
+        assert_eq!(ys, vec![0, 2, 4, 6, 8, 20, 22, 24, 26, 28]);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{Color, ElementId, Page, PageId, Point, TimedElement};
+use rstar::{RTreeObject, AABB};
+
+/// Identifies a bookmark within its journal.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct BookmarkId(u64);
+
+impl BookmarkId {
+    pub(crate) fn new(id: u64) -> Self {
+        BookmarkId(id)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A labelled location inside a journal.
+///
+/// A bookmark points to a page and optionally to an area of it or to an element. Bookmarks are
+/// never moved to another target: once their page or element is removed they are dangling, see
+/// `Journal::dangling_bookmarks`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Bookmark {
+    id: BookmarkId,
+    label: String,
+    color: Color,
+    page: PageId,
+    viewport: Option<AABB<Point>>,
+    element: Option<ElementId>,
+}
+
+impl Bookmark {
+    pub(crate) fn new(
+        id: BookmarkId,
+        label: String,
+        color: Color,
+        page: PageId,
+        viewport: Option<AABB<Point>>,
+        element: Option<ElementId>,
+    ) -> Self {
+        Bookmark {
+            id,
+            label,
+            color,
+            page,
+            viewport,
+            element,
+        }
+    }
+
+    pub fn id(&self) -> BookmarkId {
+        self.id
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = label.into();
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn page(&self) -> PageId {
+        self.page
+    }
+
+    pub fn viewport(&self) -> Option<AABB<Point>> {
+        self.viewport
+    }
+
+    pub fn element(&self) -> Option<ElementId> {
+        self.element
+    }
+
+    /// Returns whether the label contains the query, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        self.label.to_lowercase().contains(&query.to_lowercase())
+    }
+}
+
+/// The location a bookmark currently leads to.
+#[derive(Debug)]
+pub struct ResolvedBookmark<'a> {
+    /// The current index of the page, bookmarks follow their page when pages are moved.
+    pub index: usize,
+    pub page: &'a Page,
+    /// The current area of the element for element bookmarks, otherwise the area of the bookmark.
+    pub viewport: Option<AABB<Point>>,
+    /// The bookmarked element, `None` for bookmarks of pages and areas.
+    pub element: Option<&'a TimedElement>,
+}
+
+impl<'a> ResolvedBookmark<'a> {
+    /// Returns `None` if the bookmarked element is not on the page anymore.
+    pub(crate) fn new(bookmark: &Bookmark, index: usize, page: &'a Page) -> Option<Self> {
+        let element = match bookmark.element {
+            Some(id) => Some(page.get(id)?),
+            None => None,
+        };
+        Some(ResolvedBookmark {
+            index,
+            page,
+            viewport: element
+                .map(|element| element.envelope())
+                .or(bookmark.viewport),
+            element,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(label: &str) -> Bookmark {
+        Bookmark::new(
+            BookmarkId::new(0),
+            label.to_string(),
+            Color::rgb(255, 0, 0),
+            Page::default().id(),
+            Some(AABB::from_corners((0, 0).into(), (10, 10).into())),
+            Some(ElementId::new(1)),
+        )
+    }
+
+    #[test]
+    fn matches_labels_ignoring_case() {
+        let bookmark = bookmark("Maxwell Equations");
+        assert!(bookmark.matches("maxwell"));
+        assert!(bookmark.matches("EQUATIONS"));
+        assert!(!bookmark.matches("schrödinger"));
+    }
+
+    #[test]
+    fn element_bookmarks_do_not_resolve_without_their_element() {
+        let page = Page::default();
+        assert!(ResolvedBookmark::new(&bookmark("Notes"), 0, &page).is_none());
+    }
+}
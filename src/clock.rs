@@ -0,0 +1,58 @@
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+/// The source of wall-clock time for timestamps on elements.
+///
+/// Inject a custom clock with `Page::set_clock` to get deterministic timestamps.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mocks {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// A clock that starts at the epoch and only advances when told to.
+    #[derive(Debug, Default)]
+    pub struct ManualClock {
+        elapsed: Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        pub fn at(seconds: u64) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+        }
+
+        pub fn set(&self, seconds: u64) {
+            *self.elapsed.lock().unwrap() = Duration::from_secs(seconds);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + *self.elapsed.lock().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn system_clock_is_after_epoch() {
+        assert!(SystemClock.now() > SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,33 @@
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color {
+            r,
+            g,
+            b,
+            a: u8::MAX,
+        }
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_is_opaque() {
+        let color = Color::rgb(1, 2, 3);
+        assert_eq!(color, Color::rgba(1, 2, 3, 255));
+    }
+}
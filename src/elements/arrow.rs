@@ -0,0 +1,96 @@
+use std::any::Any;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Point;
+
+/// A free standing arrow from `tail` to `head`.
+///
+/// Unlike a `Connector`, an arrow is not attached to other elements.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Arrow {
+    tail: Point,
+    head: Point,
+}
+
+impl Arrow {
+    pub fn new(tail: Point, head: Point) -> Self {
+        Arrow { tail, head }
+    }
+
+    pub fn tail(&self) -> Point {
+        self.tail
+    }
+
+    pub fn head(&self) -> Point {
+        self.head
+    }
+}
+
+impl RTreeObject for Arrow {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_corners(self.tail, self.head)
+    }
+}
+
+impl Element for Arrow {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+impl Transform for Arrow {
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        self.tail = affine.apply(self.tail);
+        self.head = affine.apply(self.head);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correct_envelope() {
+        let arrow = Arrow::new((5, 0).into(), (0, 3).into());
+        assert_eq!(arrow.envelope().lower(), (0, 0));
+        assert_eq!(arrow.envelope().upper(), (5, 3));
+    }
+
+    #[test]
+    fn transforms_endpoints() {
+        let mut arrow = Arrow::new((0, 0).into(), (1, 0).into());
+        arrow.transform(&Affine::translate(1.0, 2.0));
+        assert_eq!(arrow.tail(), (1, 2));
+        assert_eq!(arrow.head(), (2, 2));
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let arrow = Arrow::new((0, 0).into(), (1, 0).into());
+        let any = arrow.as_any();
+        assert!(any.downcast_ref::<Arrow>().is_some());
+    }
+}
@@ -0,0 +1,215 @@
+use std::any::Any;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::Element;
+use crate::ElementId;
+use crate::Point;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum AnchorPoint {
+    Center,
+    MinX,
+    MaxX,
+    MinY,
+    MaxY,
+    /// An offset relative to the lower corner of the envelope.
+    Offset(Point),
+}
+
+impl AnchorPoint {
+    pub fn resolve(&self, envelope: &AABB<Point>) -> Point {
+        let lower = envelope.lower();
+        let upper = envelope.upper();
+        let center = lower.midpoint(upper);
+        match self {
+            AnchorPoint::Center => center,
+            AnchorPoint::MinX => Point {
+                x: lower.x,
+                ..center
+            },
+            AnchorPoint::MaxX => Point {
+                x: upper.x,
+                ..center
+            },
+            AnchorPoint::MinY => Point {
+                y: lower.y,
+                ..center
+            },
+            AnchorPoint::MaxY => Point {
+                y: upper.y,
+                ..center
+            },
+            AnchorPoint::Offset(offset) => lower + offset.to_vector(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Anchor {
+    element: ElementId,
+    point: AnchorPoint,
+}
+
+impl Anchor {
+    pub fn new(element: ElementId, point: AnchorPoint) -> Self {
+        Anchor { element, point }
+    }
+
+    pub fn element(&self) -> ElementId {
+        self.element
+    }
+
+    pub fn point(&self) -> AnchorPoint {
+        self.point
+    }
+}
+
+/// An arrow between two elements of the same page.
+///
+/// The endpoints are derived from the envelopes of the referenced elements. The page re-routes a
+/// connector whenever one of its referenced elements is updated through the page, also while the
+/// element is part of a group. If a referenced element is removed from the page, the connector
+/// keeps the last position of that endpoint, see `Page::dangling_connectors`.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Connector {
+    source: Anchor,
+    target: Anchor,
+    start: Point,
+    end: Point,
+}
+
+impl Connector {
+    pub fn new(source: Anchor, target: Anchor) -> Self {
+        Connector {
+            source,
+            target,
+            start: Point::new(0, 0),
+            end: Point::new(0, 0),
+        }
+    }
+
+    pub fn source(&self) -> Anchor {
+        self.source
+    }
+
+    pub fn target(&self) -> Anchor {
+        self.target
+    }
+
+    pub fn start(&self) -> Point {
+        self.start
+    }
+
+    pub fn end(&self) -> Point {
+        self.end
+    }
+
+    pub fn references(&self, id: ElementId) -> bool {
+        self.source.element == id || self.target.element == id
+    }
+
+    /// Recomputes the endpoints from the envelopes of the referenced elements.
+    ///
+    /// An endpoint whose element is unknown keeps its last position.
+    pub fn reroute(&mut self, source: Option<&AABB<Point>>, target: Option<&AABB<Point>>) {
+        if let Some(envelope) = source {
+            self.start = self.source.point.resolve(envelope);
+        }
+        if let Some(envelope) = target {
+            self.end = self.target.point.resolve(envelope);
+        }
+    }
+}
+
+impl RTreeObject for Connector {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_corners(self.start, self.end)
+    }
+}
+
+impl Element for Connector {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> AABB<Point> {
+        AABB::from_corners((2, 4).into(), (6, 10).into())
+    }
+
+    #[test]
+    fn resolves_anchor_points() {
+        assert_eq!(AnchorPoint::Center.resolve(&envelope()), (4, 7));
+        assert_eq!(AnchorPoint::MinX.resolve(&envelope()), (2, 7));
+        assert_eq!(AnchorPoint::MaxX.resolve(&envelope()), (6, 7));
+        assert_eq!(AnchorPoint::MinY.resolve(&envelope()), (4, 4));
+        assert_eq!(AnchorPoint::MaxY.resolve(&envelope()), (4, 10));
+        assert_eq!(
+            AnchorPoint::Offset((1, 2).into()).resolve(&envelope()),
+            (3, 6)
+        );
+    }
+
+    #[test]
+    fn references_source_and_target() {
+        let connector = Connector::new(
+            Anchor::new(ElementId::new(1), AnchorPoint::Center),
+            Anchor::new(ElementId::new(2), AnchorPoint::Center),
+        );
+        assert!(connector.references(ElementId::new(1)));
+        assert!(connector.references(ElementId::new(2)));
+        assert!(!connector.references(ElementId::new(3)));
+    }
+
+    #[test]
+    fn reroute_moves_endpoints_and_envelope() {
+        let mut connector = Connector::new(
+            Anchor::new(ElementId::new(1), AnchorPoint::MaxX),
+            Anchor::new(ElementId::new(2), AnchorPoint::MinX),
+        );
+        let target = AABB::from_corners((10, 0).into(), (12, 4).into());
+        connector.reroute(Some(&envelope()), Some(&target));
+        assert_eq!(connector.start(), (6, 7));
+        assert_eq!(connector.end(), (10, 2));
+        assert_eq!(connector.envelope().lower(), (6, 2));
+        assert_eq!(connector.envelope().upper(), (10, 7));
+    }
+
+    #[test]
+    fn reroute_keeps_endpoint_of_unknown_element() {
+        let mut connector = Connector::new(
+            Anchor::new(ElementId::new(1), AnchorPoint::Center),
+            Anchor::new(ElementId::new(2), AnchorPoint::Center),
+        );
+        connector.reroute(Some(&envelope()), Some(&envelope()));
+        connector.reroute(None, Some(&AABB::from_point((0, 0).into())));
+        assert_eq!(connector.start(), (4, 7));
+        assert_eq!(connector.end(), (0, 0));
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let connector = Connector::new(
+            Anchor::new(ElementId::new(1), AnchorPoint::Center),
+            Anchor::new(ElementId::new(2), AnchorPoint::Center),
+        );
+        let any = connector.as_any();
+        let connector_ref = any.downcast_ref::<Connector>().unwrap();
+        assert_eq!(connector_ref.source().element(), ElementId::new(1));
+    }
+}
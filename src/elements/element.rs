@@ -1,15 +1,53 @@
 use crate::point::Point;
+use crate::Affine;
 use rstar::{RTreeObject, AABB};
 use std::any::Any;
 use std::fmt::Debug;
 
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Default, Clone, Copy, Hash)]
+pub struct ElementId(u128);
+
+impl ElementId {
+    pub fn new(id: u128) -> Self {
+        ElementId(id)
+    }
+
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+}
+
 pub trait Element: RTreeObject<Envelope = AABB<Point>> + Debug + Sync {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clone_box(&self) -> Box<dyn Element>;
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        None
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        None
+    }
 }
 
-#[cfg(test)]
+pub trait Transform {
+    /// Whether the element can apply the transformation.
+    ///
+    /// Elements refuse transformations they can neither represent nor replace, e.g. a rotated
+    /// `Text`. Refused transformations are not applied by `TimedElement::transform`.
+    fn supports(&self, _affine: &Affine) -> bool {
+        true
+    }
 
+    /// Applies the transformation in place.
+    ///
+    /// Elements that cannot represent the result, e.g. a `Rectangle` that is rotated, return a
+    /// replacement element instead. Only called if `supports` returns `true`.
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>>;
+}
+
+#[cfg(test)]
 pub(crate) mod mocks {
     use super::*;
     use mockall::mock;
@@ -38,5 +76,8 @@ pub(crate) mod mocks {
         fn as_any_mut(&mut self) -> &mut dyn Any {
             self
         }
+        fn clone_box(&self) -> Box<dyn Element> {
+            Box::new(MockElement::new())
+        }
     }
 }
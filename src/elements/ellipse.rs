@@ -0,0 +1,144 @@
+use std::any::Any;
+use std::f64::consts::TAU;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Point;
+use crate::Polygon;
+use crate::Vector;
+
+/// An axis aligned ellipse.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Ellipse {
+    center: Point,
+    radius_x: i128,
+    radius_y: i128,
+}
+
+impl Ellipse {
+    /// The number of corners of the polygon that replaces an ellipse which is no longer axis
+    /// aligned after a transformation.
+    pub const POLYGON_CORNERS: usize = 64;
+
+    pub fn new(center: Point, radius_x: i128, radius_y: i128) -> Self {
+        Ellipse {
+            center,
+            radius_x: radius_x.abs(),
+            radius_y: radius_y.abs(),
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    pub fn radius_x(&self) -> i128 {
+        self.radius_x
+    }
+
+    pub fn radius_y(&self) -> i128 {
+        self.radius_y
+    }
+
+    /// Returns a point on the outline for the given angle.
+    pub fn point_at(&self, radians: f64) -> Point {
+        let (sin, cos) = radians.sin_cos();
+        self.center
+            + Vector::new(
+                (self.radius_x as f64 * cos).round() as i128,
+                (self.radius_y as f64 * sin).round() as i128,
+            )
+    }
+}
+
+impl RTreeObject for Ellipse {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let radius = Vector::new(self.radius_x, self.radius_y);
+        AABB::from_corners(self.center - radius, self.center + radius)
+    }
+}
+
+impl Element for Ellipse {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+impl Transform for Ellipse {
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        if !affine.preserves_axes() {
+            return Some(Box::new(Polygon::from_iter(
+                (0..Self::POLYGON_CORNERS).map(|i| {
+                    affine.apply(self.point_at(TAU * i as f64 / Self::POLYGON_CORNERS as f64))
+                }),
+            )));
+        }
+        let lower = affine.apply(self.envelope().lower());
+        let upper = affine.apply(self.envelope().upper());
+        let radius = (upper - lower) / 2;
+        *self = Ellipse::new(lower + radius, radius.x, radius.y);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correct_envelope() {
+        let ellipse = Ellipse::new((10, 10).into(), 5, -3);
+        assert_eq!(ellipse.envelope().lower(), (5, 7));
+        assert_eq!(ellipse.envelope().upper(), (15, 13));
+    }
+
+    #[test]
+    fn point_at_lies_on_outline() {
+        let ellipse = Ellipse::new((0, 0).into(), 10, 5);
+        assert_eq!(ellipse.point_at(0.0), (10, 0));
+        assert_eq!(ellipse.point_at(std::f64::consts::FRAC_PI_2), (0, 5));
+    }
+
+    #[test]
+    fn scaling_keeps_ellipse() {
+        let mut ellipse = Ellipse::new((10, 10).into(), 5, 3);
+        assert!(ellipse.transform(&Affine::scale(2.0, 1.0)).is_none());
+        assert_eq!(ellipse, Ellipse::new((20, 10).into(), 10, 3));
+    }
+
+    #[test]
+    fn rotation_turns_ellipse_into_polygon() {
+        let mut ellipse = Ellipse::new((0, 0).into(), 10, 5);
+        let replacement = ellipse.transform(&Affine::rotate(0.5)).unwrap();
+        let polygon = replacement.as_any().downcast_ref::<Polygon>().unwrap();
+        assert_eq!(polygon.len(), Ellipse::POLYGON_CORNERS);
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let ellipse = Ellipse::new((0, 0).into(), 1, 1);
+        let any = ellipse.as_any();
+        assert!(any.downcast_ref::<Ellipse>().is_some());
+    }
+}
@@ -0,0 +1,251 @@
+use std::any::Any;
+
+use rstar::{Envelope, RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Line;
+use crate::Point;
+
+/// Turns TeX source into vector paths.
+///
+/// The paths are relative to the position of the formula. Returns `None` if the source cannot be
+/// rendered.
+#[cfg_attr(test, mockall::automock)]
+pub trait FormulaRenderer {
+    fn render(&self, source: &str) -> Option<Vec<Line>>;
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct FormulaRendering {
+    paths: Vec<Line>,
+    lower: Point,
+    upper: Point,
+}
+
+impl FormulaRendering {
+    /// Creates a rendering from paths relative to the position of the formula.
+    ///
+    /// The bounding box always includes the origin, so a formula stays selectable at its position
+    /// even if the paths start away from it or are empty.
+    pub fn new(paths: Vec<Line>) -> Self {
+        let mut envelope = AABB::from_point(Point::new(0, 0));
+        for path in paths.iter().filter(|path| path.iter().next().is_some()) {
+            envelope.merge(&path.envelope());
+        }
+        FormulaRendering {
+            paths,
+            lower: envelope.lower(),
+            upper: envelope.upper(),
+        }
+    }
+
+    pub fn paths(&self) -> &[Line] {
+        &self.paths
+    }
+
+    /// The bounds of the paths and the origin, see `FormulaRendering::new`.
+    pub fn bounding_box(&self) -> AABB<Point> {
+        AABB::from_corners(self.lower, self.upper)
+    }
+}
+
+/// A math formula given as TeX source together with a cached rendering.
+///
+/// Changing the source invalidates the rendering. `Page::render_formulas` renders all formulas of
+/// a page that are out of date and reindexes them.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Formula {
+    position: Point,
+    source: String,
+    rendering: Option<FormulaRendering>,
+    stale: bool,
+}
+
+impl Formula {
+    pub fn new(position: Point, source: impl Into<String>) -> Self {
+        Formula {
+            position,
+            source: source.into(),
+            rendering: None,
+            stale: true,
+        }
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn set_source(&mut self, source: impl Into<String>) {
+        let source = source.into();
+        if source != self.source {
+            self.source = source;
+            self.stale = true;
+        }
+    }
+
+    pub fn rendering(&self) -> Option<&FormulaRendering> {
+        self.rendering.as_ref()
+    }
+
+    pub fn needs_rendering(&self) -> bool {
+        self.stale
+    }
+
+    /// Renders the current source, replacing the cached rendering.
+    ///
+    /// Returns `false` if the renderer failed, in which case the formula has no rendering until
+    /// the source changes.
+    pub fn render(&mut self, renderer: &dyn FormulaRenderer) -> bool {
+        self.rendering = renderer.render(&self.source).map(FormulaRendering::new);
+        self.stale = false;
+        self.rendering.is_some()
+    }
+}
+
+impl RTreeObject for Formula {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let Some(rendering) = &self.rendering else {
+            return AABB::from_point(self.position);
+        };
+        AABB::from_corners(
+            self.position + rendering.lower.to_vector(),
+            self.position + rendering.upper.to_vector(),
+        )
+    }
+}
+
+impl Element for Formula {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+/// Only translations are supported, the model cannot scale or rotate a rendering.
+impl Transform for Formula {
+    fn supports(&self, affine: &Affine) -> bool {
+        affine.is_translation()
+    }
+
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        self.position = affine.apply(self.position);
+        None
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::TimedElement;
+    use mockall::predicate::eq;
+
+    #[test]
+    fn refuses_rotation() {
+        let mut formula = TimedElement::new(Formula::new((10, 20).into(), "x^2"), 0, 1);
+        assert!(!formula.transform(&Affine::rotate(std::f64::consts::FRAC_PI_2)));
+        assert!(formula.transform(&Affine::translate(5.0, 0.0)));
+        assert_eq!(
+            formula.downcast_ref::<Formula>().unwrap().position(),
+            (15, 20)
+        );
+    }
+
+    pub(crate) fn renderer(source: &'static str) -> MockFormulaRenderer {
+        let mut renderer = MockFormulaRenderer::new();
+        renderer
+            .expect_render()
+            .with(eq(source))
+            .return_const(Some(vec![Line::from_iter([(0, -2), (10, 4)].iter())]));
+        renderer
+    }
+
+    #[test]
+    fn rendering_bounding_box_includes_origin_and_paths() {
+        let rendering = FormulaRendering::new(vec![
+            Line::from_iter([(2, 3), (4, 5)].iter()),
+            Line::default(),
+        ]);
+        assert_eq!(rendering.bounding_box().lower(), (0, 0));
+        assert_eq!(rendering.bounding_box().upper(), (4, 5));
+
+        let below = FormulaRendering::new(vec![Line::from_iter([(-4, -5), (-2, -3)].iter())]);
+        assert_eq!(below.bounding_box().lower(), (-4, -5));
+        assert_eq!(below.bounding_box().upper(), (0, 0));
+
+        let empty = FormulaRendering::new(Vec::new());
+        assert_eq!(empty.bounding_box().lower(), (0, 0));
+        assert_eq!(empty.bounding_box().upper(), (0, 0));
+    }
+
+    #[test]
+    fn unrendered_envelope_is_position() {
+        let formula = Formula::new((3, 4).into(), "x^2");
+        assert!(formula.needs_rendering());
+        assert_eq!(formula.envelope().lower(), (3, 4));
+        assert_eq!(formula.envelope().upper(), (3, 4));
+    }
+
+    #[test]
+    fn envelope_comes_from_rendering() {
+        let mut formula = Formula::new((3, 4).into(), "x^2");
+        assert!(formula.render(&renderer("x^2")));
+        assert!(!formula.needs_rendering());
+        assert_eq!(formula.envelope().lower(), (3, 2));
+        assert_eq!(formula.envelope().upper(), (13, 8));
+    }
+
+    #[test]
+    fn changing_source_requests_rendering() {
+        let mut formula = Formula::new((0, 0).into(), "x^2");
+        formula.render(&renderer("x^2"));
+
+        formula.set_source("x^2");
+        assert!(!formula.needs_rendering());
+        formula.set_source("y^2");
+        assert!(formula.needs_rendering());
+    }
+
+    #[test]
+    fn failed_rendering_clears_cache() {
+        let mut formula = Formula::new((0, 0).into(), "x^2");
+        formula.render(&renderer("x^2"));
+        formula.set_source("\\frac{");
+
+        let mut failing = MockFormulaRenderer::new();
+        failing.expect_render().return_const(None);
+        assert!(!formula.render(&failing));
+
+        assert!(formula.rendering().is_none());
+        assert!(!formula.needs_rendering());
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let formula = Formula::new((0, 0).into(), "x^2");
+        let any = formula.as_any();
+        assert!(any.downcast_ref::<Formula>().is_some());
+    }
+}
@@ -0,0 +1,198 @@
+use std::any::Any;
+use std::slice::{Iter, IterMut};
+
+use rstar::{Envelope, RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Point;
+use crate::TimedElement;
+
+/// A composite of several elements that is moved and styled as a unit.
+///
+/// Children keep their id, `z_index` and `t_index` so they can be restored unchanged when the
+/// group is dissolved with `Page::ungroup`.
+#[derive(Debug, Default, Clone)]
+pub struct Group {
+    children: Vec<TimedElement>,
+}
+
+impl Group {
+    pub(crate) fn new(children: Vec<TimedElement>) -> Self {
+        Group { children }
+    }
+
+    pub(crate) fn into_children(self) -> Vec<TimedElement> {
+        self.children
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, TimedElement> {
+        self.into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, TimedElement> {
+        self.into_iter()
+    }
+}
+
+impl RTreeObject for Group {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let mut children = self.children.iter();
+        let Some(first) = children.next() else {
+            return AABB::from_point(Point { x: 0, y: 0 });
+        };
+        children.fold(first.envelope(), |mut envelope, child| {
+            envelope.merge(&child.envelope());
+            envelope
+        })
+    }
+}
+
+impl Element for Group {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+/// Supported if every child supports the transformation, so a group is never transformed
+/// partially. Children that cannot represent the result are replaced inside the group.
+impl Transform for Group {
+    fn supports(&self, affine: &Affine) -> bool {
+        self.children
+            .iter()
+            .all(|child| child.supports_transform(affine))
+    }
+
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        for child in self.children.iter_mut() {
+            let transformed = child.transform(affine);
+            debug_assert!(transformed, "checked by Group::supports");
+        }
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a Group {
+    type Item = &'a TimedElement;
+    type IntoIter = Iter<'a, TimedElement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.children.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Group {
+    type Item = &'a mut TimedElement;
+    type IntoIter = IterMut<'a, TimedElement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.children.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElementId, Formula, Line, Polygon, Rectangle};
+
+    #[test]
+    fn correct_empty_envelope() {
+        let group = Group::default();
+        assert!(group.is_empty());
+        assert_eq!(group.envelope().lower(), (0, 0));
+        assert_eq!(group.envelope().upper(), (0, 0));
+    }
+
+    #[test]
+    fn envelope_is_union_of_children() {
+        let group = Group::new(vec![
+            TimedElement::new(Rectangle::new((1, 1).into(), (2, 3).into()), 0, 1),
+            TimedElement::new(Line::from_iter([(5, -2), (4, 0)].iter()), 0, 2),
+        ]);
+        assert_eq!(group.len(), 2);
+        assert_eq!(group.envelope().lower(), (1, -2));
+        assert_eq!(group.envelope().upper(), (5, 3));
+    }
+
+    #[test]
+    fn can_iterate_and_modify_children() {
+        let mut group = Group::new(vec![TimedElement::new(
+            Line::from_iter([(1, 1)].iter()),
+            0,
+            1,
+        )]);
+        for child in group.iter_mut() {
+            child
+                .downcast_mut::<Line>()
+                .unwrap()
+                .push_back((3, 3).into());
+        }
+        assert_eq!(group.envelope().upper(), (3, 3));
+        assert_eq!(group.iter().next().unwrap().t_index(), 1);
+    }
+
+    #[test]
+    fn refuses_transforms_a_child_refuses() {
+        let mut group = TimedElement::new(
+            Group::new(vec![
+                TimedElement::new(Line::from_iter([(0, 0), (2, 0)].iter()), 0, 1),
+                TimedElement::new(Formula::new((4, 4).into(), "x"), 0, 2),
+            ]),
+            0,
+            3,
+        );
+        assert!(!group.transform(&Affine::rotate(std::f64::consts::FRAC_PI_2)));
+        let group = group.downcast_ref::<Group>().unwrap();
+        assert_eq!(group.iter().next().unwrap().envelope().upper(), (2, 0));
+    }
+
+    #[test]
+    fn replaces_children_that_cannot_represent_the_transform() {
+        let mut group = Group::new(vec![TimedElement::new(
+            Rectangle::new((-1, -1).into(), (1, 1).into()),
+            0,
+            1,
+        )]);
+        assert!(group.supports(&Affine::rotate(std::f64::consts::FRAC_PI_4)));
+        assert!(group
+            .transform(&Affine::rotate(std::f64::consts::FRAC_PI_4))
+            .is_none());
+        let child = group.iter().next().unwrap();
+        assert!(child.downcast_ref::<Polygon>().is_some());
+        assert_eq!(child.id(), ElementId::new(1));
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let group = Group::default();
+        let any = group.as_any();
+        assert!(any.downcast_ref::<Group>().is_some());
+    }
+}
@@ -1,29 +1,249 @@
 use std::any::Any;
 use std::collections::linked_list::{Iter, IterMut};
 use std::collections::LinkedList;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
 
 use rstar::{RTreeObject, AABB};
 
+use crate::elements::element::Transform;
+use crate::stroke::smooth::{resample, Sample};
+use crate::Affine;
 use crate::Element;
+use crate::FloatPoint;
 use crate::Point;
+use crate::Simplification;
+use crate::Smoothing;
 
-#[derive(Default, PartialEq, Eq, Debug, Clone, Hash)]
+/// A freehand line.
+///
+/// Points are stored in model units together with their sub-unit remainder, so that lines built
+/// from float input, transformed, smoothed or resampled do not accumulate rounding errors. Lines
+/// compare equal if their points, including the remainders, are equal.
+#[derive(Default, Debug, Clone)]
 pub struct Line {
     points: LinkedList<Point>,
+    /// The sub-unit remainder of each point, `None` if all points are exact.
+    remainders: Option<LinkedList<FloatPoint>>,
+    pressure: Option<LinkedList<u16>>,
+    timestamps: Option<LinkedList<Duration>>,
+}
+
+impl PartialEq for Line {
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+            && self.remainders().eq(other.remainders())
+            && self.pressure == other.pressure
+            && self.timestamps == other.timestamps
+    }
+}
+
+// `FloatPoint::split` never produces NaN remainders, so equality is reflexive.
+impl Eq for Line {}
+
+impl Hash for Line {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.points.hash(state);
+        self.pressure.hash(state);
+        self.timestamps.hash(state);
+    }
 }
 
 impl Line {
+    /// Creates a line from points with their stylus pressure.
+    pub fn with_pressure<P, T>(samples: T) -> Self
+    where
+        P: Into<Point>,
+        T: IntoIterator<Item = (P, u16)>,
+    {
+        let (points, pressure) = samples
+            .into_iter()
+            .map(|(point, pressure)| (point.into(), pressure))
+            .unzip();
+        Line {
+            points,
+            remainders: None,
+            pressure: Some(pressure),
+            timestamps: None,
+        }
+    }
+
+    /// Creates a line from points with sub-unit precision.
+    ///
+    /// Non-finite coordinates are clamped, see `FloatPoint::split`.
+    pub fn from_float_points<T: IntoIterator<Item = FloatPoint>>(points: T) -> Self {
+        let mut line = Line::default();
+        for point in points {
+            line.push_back_float(point);
+        }
+        line
+    }
+
+    /// Like `push_back`, but keeps the sub-unit remainder of the point.
+    ///
+    /// Non-finite coordinates are clamped, see `FloatPoint::split`.
+    pub fn push_back_float(&mut self, point: FloatPoint) {
+        let (point, remainder) = point.split();
+        let exact = self.points.len();
+        self.remainders
+            .get_or_insert_with(|| (0..exact).map(|_| FloatPoint::default()).collect());
+        self.push_back(point);
+        if let Some(last) = self.remainders.as_mut().and_then(LinkedList::back_mut) {
+            *last = remainder;
+        }
+    }
+
+    /// Appends a point. If the line has pressure information or timestamps, the last pressure
+    /// and timestamp are repeated.
     pub fn push_back(&mut self, point: Point) {
+        if let Some(remainders) = &mut self.remainders {
+            remainders.push_back(FloatPoint::default());
+        }
         self.points.push_back(point);
+        if let Some(pressure) = &mut self.pressure {
+            pressure.push_back(pressure.back().copied().unwrap_or_default());
+        }
+        if let Some(timestamps) = &mut self.timestamps {
+            timestamps.push_back(timestamps.back().copied().unwrap_or_default());
+        }
     }
 
-    pub fn iter(&self) -> Iter<Point> {
+    /// Appends a point with its pressure. Points without pressure information get the same
+    /// pressure.
+    pub fn push_back_with_pressure(&mut self, point: Point, pressure: u16) {
+        self.pressure
+            .get_or_insert_with(|| self.points.iter().map(|_| pressure).collect())
+            .push_back(pressure);
+        if let Some(remainders) = &mut self.remainders {
+            remainders.push_back(FloatPoint::default());
+        }
+        self.points.push_back(point);
+        if let Some(timestamps) = &mut self.timestamps {
+            timestamps.push_back(timestamps.back().copied().unwrap_or_default());
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, Point> {
         self.into_iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<Point> {
+    /// Iterates mutably over the points. The points lose their sub-unit remainder.
+    pub fn iter_mut(&mut self) -> IterMut<'_, Point> {
         self.into_iter()
     }
+
+    /// Iterates over the points with their sub-unit remainder.
+    pub fn iter_float(&self) -> impl Iterator<Item = FloatPoint> + '_ {
+        self.points
+            .iter()
+            .zip(self.remainders())
+            .map(|(point, remainder)| FloatPoint::from_parts(*point, remainder))
+    }
+
+    fn remainders(&self) -> impl Iterator<Item = FloatPoint> + '_ {
+        let exact = std::iter::repeat(FloatPoint::default());
+        let remainders = self.remainders.iter().flatten().copied();
+        remainders.chain(exact).take(self.points.len())
+    }
+
+    /// Replaces the points, keeping their sub-unit remainders.
+    fn set_float_points<T: IntoIterator<Item = FloatPoint>>(&mut self, points: T) {
+        let (points, remainders): (LinkedList<Point>, LinkedList<FloatPoint>) =
+            points.into_iter().map(|point| point.split()).unzip();
+        self.points = points;
+        let exact = remainders
+            .iter()
+            .all(|remainder| *remainder == FloatPoint::default());
+        self.remainders = (!exact).then_some(remainders);
+    }
+
+    pub fn pressure(&self) -> Option<Iter<'_, u16>> {
+        self.pressure.as_ref().map(|pressure| pressure.iter())
+    }
+
+    /// The time at which each point was drawn, relative to the start of the line.
+    pub fn timestamps(&self) -> Option<Iter<'_, Duration>> {
+        self.timestamps.as_ref().map(|timestamps| timestamps.iter())
+    }
+
+    /// Sets the time at which each point was drawn, relative to the start of the line.
+    ///
+    /// Returns `false` and leaves the line unchanged if the number of timestamps does not match
+    /// the number of points.
+    pub fn set_timestamps<T: IntoIterator<Item = Duration>>(&mut self, timestamps: T) -> bool {
+        let timestamps: LinkedList<Duration> = timestamps.into_iter().collect();
+        if timestamps.len() != self.points.len() {
+            return false;
+        }
+        self.timestamps = Some(timestamps);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Removes redundant points and returns how many were removed.
+    pub fn simplify(&mut self, simplification: Simplification) -> usize {
+        let points: Vec<Point> = self.points.iter().copied().collect();
+        let retained = simplification.retained(&points);
+        let removed = retained.iter().filter(|keep| !**keep).count();
+        self.points = retain(&self.points, &retained);
+        if let Some(remainders) = &self.remainders {
+            self.remainders = Some(retain(remainders, &retained));
+        }
+        if let Some(pressure) = &self.pressure {
+            self.pressure = Some(retain(pressure, &retained));
+        }
+        if let Some(timestamps) = &self.timestamps {
+            self.timestamps = Some(retain(timestamps, &retained));
+        }
+        removed
+    }
+
+    pub fn smooth(&mut self, smoothing: Smoothing) {
+        let smoothed = smoothing.apply(&self.samples());
+        self.set_samples(smoothed);
+    }
+
+    /// Replaces the points with points spaced `spacing` apart along the line.
+    pub fn resample(&mut self, spacing: f64) {
+        let resampled = resample(&self.samples(), spacing);
+        self.set_samples(resampled);
+    }
+
+    fn samples(&self) -> Vec<Sample> {
+        let pressure = self.pressure.iter().flatten().chain(std::iter::repeat(&0));
+        let timestamps =
+            (self.timestamps.iter().flatten()).chain(std::iter::repeat(&Duration::ZERO));
+        self.iter_float()
+            .zip(pressure)
+            .zip(timestamps)
+            .map(|((point, pressure), time)| Sample::from_float(point, *pressure).with_time(*time))
+            .collect()
+    }
+
+    fn set_samples(&mut self, samples: Vec<Sample>) {
+        self.set_float_points(samples.iter().map(Sample::float_point));
+        if self.pressure.is_some() {
+            self.pressure = Some(samples.iter().map(Sample::pressure).collect());
+        }
+        if self.timestamps.is_some() {
+            self.timestamps = Some(samples.iter().map(Sample::time).collect());
+        }
+    }
+}
+
+fn retain<T: Copy>(values: &LinkedList<T>, retained: &[bool]) -> LinkedList<T> {
+    values
+        .iter()
+        .zip(retained)
+        .filter_map(|(value, keep)| keep.then_some(*value))
+        .collect()
 }
 
 impl<P> FromIterator<P> for Line
@@ -33,6 +253,9 @@ where
     fn from_iter<T: IntoIterator<Item = P>>(points: T) -> Self {
         Line {
             points: LinkedList::from_iter(points.into_iter().map(|p| -> Point { p.into() })),
+            remainders: None,
+            pressure: None,
+            timestamps: None,
         }
     }
 }
@@ -56,6 +279,29 @@ impl Element for Line {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+impl Transform for Line {
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        let points: Vec<FloatPoint> = self
+            .iter_float()
+            .map(|point| affine.apply_float(point))
+            .collect();
+        self.set_float_points(points);
+        None
+    }
 }
 
 impl<'a> IntoIterator for &'a Line {
@@ -72,11 +318,13 @@ impl<'a> IntoIterator for &'a mut Line {
     type IntoIter = IterMut<'a, Point>;
 
     fn into_iter(self) -> Self::IntoIter {
+        self.remainders = None;
         self.points.iter_mut()
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -165,4 +413,170 @@ mod tests {
         line_ref.push_back((3, 4).into());
         assert_eq!(*line_ref.iter().nth(3).unwrap(), (3, 4))
     }
+
+    #[test]
+    fn keeps_sub_unit_precision() {
+        let mut line = Line::from_float_points([FloatPoint::new(0.25, 0.5)]);
+        line.push_back((2, 2).into());
+        assert!(line.iter().eq([(0, 1), (2, 2)].iter()));
+        assert!(line
+            .iter_float()
+            .eq([FloatPoint::new(0.25, 0.5), FloatPoint::new(2.0, 2.0)]));
+    }
+
+    #[test]
+    fn float_and_integer_lines_compare_equal() {
+        let integer = Line::from_iter([(1, 2), (3, 4)].iter());
+        let float = Line::from_float_points([FloatPoint::new(1.0, 2.0), FloatPoint::new(3.0, 4.0)]);
+        assert_eq!(integer, float);
+
+        let invalid = Line::from_float_points([FloatPoint::new(f64::NAN, f64::INFINITY)]);
+        assert_eq!(invalid, invalid.clone());
+        assert!(invalid.iter().eq([(0, i128::MAX)].iter()));
+    }
+
+    #[test]
+    fn editing_points_drops_remainders() {
+        let mut line = Line::from_float_points([FloatPoint::new(0.25, 0.5)]);
+        *line.iter_mut().next().unwrap() = (4, 4).into();
+        assert!(line.iter_float().eq([FloatPoint::new(4.0, 4.0)]));
+    }
+
+    #[test]
+    fn smoothing_and_resampling_keep_remainders() {
+        let mut line = Line::from_iter([(0, 0), (1, 0)].iter());
+        line.resample(0.5);
+        assert!(line.iter_float().eq([
+            FloatPoint::new(0.0, 0.0),
+            FloatPoint::new(0.5, 0.0),
+            FloatPoint::new(1.0, 0.0)
+        ]));
+
+        let mut line = Line::from_iter([(0, 0), (1, 1), (2, 0)].iter());
+        line.smooth(Smoothing::Chaikin(1));
+        assert_eq!(
+            line.iter_float().nth(1).unwrap(),
+            FloatPoint::new(0.25, 0.25)
+        );
+    }
+
+    #[test]
+    fn repeated_transforms_do_not_accumulate_rounding() {
+        let mut line = Line::from_iter([(1, 3), (7, -5)].iter());
+        let original = line.clone();
+        line.transform(&Affine::scale(0.5, 0.5));
+        assert!(line.iter().eq([(1, 2), (4, -3)].iter()));
+        line.transform(&Affine::scale(2.0, 2.0));
+        assert_eq!(line, original);
+
+        for _ in 0..8 {
+            line.transform(&Affine::rotate(std::f64::consts::FRAC_PI_4));
+        }
+        assert!(line.iter().eq(original.iter()));
+    }
+
+    #[test]
+    fn transforms_points() {
+        let mut line = Line::from_iter([(1, 0), (2, 2)].iter());
+        assert!(line.transform(&Affine::scale(2.0, 3.0)).is_none());
+        assert!(line.iter().eq([(2, 0), (4, 6)].iter()));
+    }
+
+    #[test]
+    fn can_get_len() {
+        let line = Line::from_iter([(5, 7), (4, 3)].iter());
+        assert_eq!(line.len(), 2);
+        assert!(!line.is_empty());
+        assert!(Line::default().is_empty());
+    }
+
+    #[test]
+    fn simplify_reports_removed_points() {
+        let mut line = Line::from_iter([(0, 0), (1, 0), (2, 0), (3, 1), (4, 0)].iter());
+        assert_eq!(line.simplify(Simplification::RamerDouglasPeucker(1.5)), 3);
+        assert!(line.iter().eq([(0, 0), (4, 0)].iter()));
+    }
+
+    #[test]
+    fn stores_pressure() {
+        let line = Line::with_pressure([((0, 0), 10), ((1, 1), 20)]);
+        assert!(line.iter().eq([(0, 0), (1, 1)].iter()));
+        assert!(line.pressure().unwrap().eq([10, 20].iter()));
+        assert!(Line::default().pressure().is_none());
+    }
+
+    #[test]
+    fn push_back_keeps_pressure_aligned() {
+        let mut line = Line::from_iter([(0, 0)].iter());
+        line.push_back_with_pressure((1, 1).into(), 30);
+        line.push_back((2, 2).into());
+        assert!(line.pressure().unwrap().eq([30, 30, 30].iter()));
+    }
+
+    #[test]
+    fn simplify_drops_pressure_of_removed_points() {
+        let mut line = Line::with_pressure([((0, 0), 1), ((1, 0), 2), ((2, 0), 3)]);
+        line.simplify(Simplification::RamerDouglasPeucker(0.5));
+        assert!(line.pressure().unwrap().eq([1, 3].iter()));
+    }
+
+    #[test]
+    fn smooth_interpolates_pressure() {
+        let mut line = Line::with_pressure([((0, 0), 0), ((40, 0), 100), ((40, 40), 200)]);
+        line.smooth(Smoothing::Chaikin(1));
+        assert_eq!(line.len(), 6);
+        assert!(line
+            .pressure()
+            .unwrap()
+            .eq([0, 25, 75, 125, 175, 200].iter()));
+    }
+
+    #[test]
+    fn smooth_without_pressure_adds_none() {
+        let mut line = Line::from_iter([(0, 0), (10, 6), (20, 0)].iter());
+        line.smooth(Smoothing::MovingAverage(1));
+        assert!(line.iter().eq([(0, 0), (10, 2), (20, 0)].iter()));
+        assert!(line.pressure().is_none());
+    }
+
+    #[test]
+    fn resample_uses_fixed_spacing() {
+        let mut line = Line::from_iter([(0, 0), (10, 0)].iter());
+        line.resample(2.5);
+        assert_eq!(line.len(), 5);
+        assert_eq!(*line.iter().nth(1).unwrap(), (3, 0));
+    }
+
+    #[test]
+    fn stores_timestamps() {
+        let mut line = Line::from_iter([(0, 0), (1, 1)].iter());
+        assert!(line.timestamps().is_none());
+        assert!(!line.set_timestamps([Duration::ZERO]));
+        assert!(line.set_timestamps([Duration::ZERO, Duration::from_millis(8)]));
+        line.push_back((2, 2).into());
+        assert!(line.timestamps().unwrap().eq([
+            Duration::ZERO,
+            Duration::from_millis(8),
+            Duration::from_millis(8)
+        ]
+        .iter()));
+    }
+
+    #[test]
+    fn simplify_and_resample_keep_timestamps_aligned() {
+        let mut line = Line::from_iter([(0, 0), (5, 0), (10, 0)].iter());
+        line.set_timestamps([0, 50, 100].map(Duration::from_millis));
+        line.simplify(Simplification::RamerDouglasPeucker(0.5));
+        assert!(line
+            .timestamps()
+            .unwrap()
+            .eq([0, 100].map(Duration::from_millis).iter()));
+
+        line.resample(2.5);
+        assert_eq!(line.timestamps().unwrap().count(), 5);
+        assert_eq!(
+            *line.timestamps().unwrap().nth(2).unwrap(),
+            Duration::from_millis(50)
+        );
+    }
 }
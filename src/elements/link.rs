@@ -0,0 +1,157 @@
+use std::any::Any;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::ElementId;
+use crate::PageId;
+use crate::Point;
+
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum LinkTarget {
+    Uri(String),
+    Page(PageId),
+    Element(PageId, ElementId),
+}
+
+/// A clickable region that refers to an external resource or to another location in the journal.
+///
+/// Links are resolved with `Journal::resolve_link`.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Link {
+    lower: Point,
+    upper: Point,
+    target: LinkTarget,
+}
+
+impl Link {
+    pub fn new(region: AABB<Point>, target: LinkTarget) -> Self {
+        Link {
+            lower: region.lower(),
+            upper: region.upper(),
+            target,
+        }
+    }
+
+    pub fn region(&self) -> AABB<Point> {
+        AABB::from_corners(self.lower, self.upper)
+    }
+
+    pub fn target(&self) -> &LinkTarget {
+        &self.target
+    }
+
+    pub fn set_target(&mut self, target: LinkTarget) {
+        self.target = target;
+    }
+}
+
+impl RTreeObject for Link {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.region()
+    }
+}
+
+impl Element for Link {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+/// Only transformations that keep the region axis aligned are supported.
+impl Transform for Link {
+    fn supports(&self, affine: &Affine) -> bool {
+        affine.preserves_axes()
+    }
+
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        let region = AABB::from_points(
+            [
+                self.lower,
+                Point {
+                    x: self.upper.x,
+                    y: self.lower.y,
+                },
+                self.upper,
+                Point {
+                    x: self.lower.x,
+                    y: self.upper.y,
+                },
+            ]
+            .map(|corner| affine.apply(corner))
+            .iter(),
+        );
+        self.lower = region.lower();
+        self.upper = region.upper();
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimedElement;
+
+    #[test]
+    fn envelope_is_region() {
+        let region = AABB::from_corners((3, 1).into(), (0, 2).into());
+        let link = Link::new(region, LinkTarget::Uri("https://example.com".into()));
+        assert_eq!(link.envelope().lower(), (0, 1));
+        assert_eq!(link.envelope().upper(), (3, 2));
+    }
+
+    #[test]
+    fn can_change_target() {
+        let region = AABB::from_point((0, 0).into());
+        let mut link = Link::new(region, LinkTarget::Uri("https://example.com".into()));
+        link.set_target(LinkTarget::Uri("https://example.org".into()));
+        assert_eq!(
+            link.target(),
+            &LinkTarget::Uri("https://example.org".into())
+        );
+    }
+
+    #[test]
+    fn refuses_rotation() {
+        let region = AABB::from_corners((0, 0).into(), (4, 2).into());
+        let mut link = TimedElement::new(
+            Link::new(region, LinkTarget::Uri("https://example.com".into())),
+            0,
+            1,
+        );
+        assert!(link.transform(&Affine::scale(2.0, 1.0)));
+        assert_eq!(link.envelope().upper(), (8, 2));
+        assert!(!link.transform(&Affine::rotate(0.3)));
+        assert_eq!(link.envelope().upper(), (8, 2));
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let link = Link::new(
+            AABB::from_point((0, 0).into()),
+            LinkTarget::Uri("https://example.com".into()),
+        );
+        let any = link.as_any();
+        assert!(any.downcast_ref::<Link>().is_some());
+    }
+}
@@ -1,4 +1,13 @@
+pub mod arrow;
+pub mod connector;
 pub mod element;
+pub mod ellipse;
+pub mod formula;
+pub mod group;
 pub mod line;
+pub mod link;
+pub mod polygon;
 pub mod rectangle;
+pub mod sticky_note;
+pub mod text;
 pub mod timed_element;
@@ -0,0 +1,189 @@
+use std::any::Any;
+use std::slice::Iter;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Point;
+
+/// A closed shape given by its corners.
+#[derive(Default, PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Polygon {
+    corners: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn len(&self) -> usize {
+        self.corners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corners.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, Point> {
+        self.corners.iter()
+    }
+
+    /// Whether the point lies inside the polygon, using the even-odd rule.
+    pub fn contains(&self, point: Point) -> bool {
+        let (x, y) = (point.x as f64, point.y as f64);
+        let mut inside = false;
+        for (i, start) in self.corners.iter().enumerate() {
+            let end = self.corners[(i + 1) % self.corners.len()];
+            let (sx, sy) = (start.x as f64, start.y as f64);
+            let (ex, ey) = (end.x as f64, end.y as f64);
+            if (sy > y) != (ey > y) && x < sx + (y - sy) * (ex - sx) / (ey - sy) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Whether all points of the path and the segments between them lie inside the polygon.
+    ///
+    /// Unlike checking the points alone, this rejects paths that leave a concave polygon between
+    /// two points. Segments that only touch an edge do not count as leaving the polygon.
+    pub fn contains_path(&self, path: &[Point]) -> bool {
+        path.iter().all(|point| self.contains(*point))
+            && path
+                .windows(2)
+                .all(|segment| !self.crosses(segment[0], segment[1]))
+    }
+
+    /// Whether the segment crosses an edge of the polygon.
+    fn crosses(&self, start: Point, end: Point) -> bool {
+        let side = |from: Point, to: Point, point: Point| (to - from).cross(point - from).signum();
+        self.corners.iter().enumerate().any(|(i, corner)| {
+            let next = self.corners[(i + 1) % self.corners.len()];
+            side(*corner, next, start) * side(*corner, next, end) < 0
+                && side(start, end, *corner) * side(start, end, next) < 0
+        })
+    }
+}
+
+impl<P> FromIterator<P> for Polygon
+where
+    P: Into<Point>,
+{
+    fn from_iter<T: IntoIterator<Item = P>>(corners: T) -> Self {
+        Polygon {
+            corners: corners.into_iter().map(|p| -> Point { p.into() }).collect(),
+        }
+    }
+}
+
+impl RTreeObject for Polygon {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        if self.corners.is_empty() {
+            return AABB::from_point(Point { x: 0, y: 0 });
+        }
+        AABB::from_points(self.corners.iter())
+    }
+}
+
+impl Element for Polygon {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+impl Transform for Polygon {
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        for corner in self.corners.iter_mut() {
+            *corner = affine.apply(*corner);
+        }
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a Polygon {
+    type Item = &'a Point;
+    type IntoIter = Iter<'a, Point>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.corners.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_iter_stores_corners() {
+        let polygon = Polygon::from_iter([(0, 0), (4, 0), (2, 3)].iter());
+        assert_eq!(polygon.len(), 3);
+        assert!(polygon.iter().eq([(0, 0), (4, 0), (2, 3)].iter()));
+    }
+
+    #[test]
+    fn contains_points_inside() {
+        let polygon = Polygon::from_iter([(0, 0), (10, 0), (10, 10), (5, 5), (0, 10)].iter());
+        assert!(polygon.contains((2, 2).into()));
+        assert!(polygon.contains((8, 8).into()));
+        assert!(!polygon.contains((5, 8).into()));
+        assert!(!polygon.contains((11, 5).into()));
+        assert!(!Polygon::default().contains((0, 0).into()));
+    }
+
+    #[test]
+    fn contains_paths_inside() {
+        let polygon = Polygon::from_iter([(0, 0), (10, 0), (10, 10), (5, 5), (0, 10)].iter());
+        let path = |points: &[(i128, i128)]| -> Vec<Point> {
+            points.iter().map(|point| point.into()).collect()
+        };
+        assert!(polygon.contains_path(&path(&[(1, 2), (9, 2), (9, 8)])));
+        assert!(!polygon.contains_path(&path(&[(2, 8), (8, 8)])));
+        assert!(!polygon.contains_path(&path(&[(2, 2), (12, 2)])));
+        assert!(polygon.contains_path(&[]));
+    }
+
+    #[test]
+    fn correct_envelope() {
+        let polygon = Polygon::from_iter([(0, 0), (4, -1), (2, 3)].iter());
+        assert_eq!(polygon.envelope().lower(), (0, -1));
+        assert_eq!(polygon.envelope().upper(), (4, 3));
+    }
+
+    #[test]
+    fn correct_empty_envelope() {
+        let polygon = Polygon::default();
+        assert!(polygon.is_empty());
+        assert_eq!(polygon.envelope().lower(), (0, 0));
+    }
+
+    #[test]
+    fn transforms_corners() {
+        let mut polygon = Polygon::from_iter([(0, 0), (4, 0), (2, 3)].iter());
+        assert!(polygon.transform(&Affine::translate(1.0, 1.0)).is_none());
+        assert!(polygon.iter().eq([(1, 1), (5, 1), (3, 4)].iter()));
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let polygon = Polygon::default();
+        let any = polygon.as_any();
+        assert!(any.downcast_ref::<Polygon>().is_some());
+    }
+}
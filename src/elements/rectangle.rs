@@ -1,15 +1,44 @@
 use std::any::Any;
 use std::cmp::{max, min};
+use std::hash::{Hash, Hasher};
 
 use rstar::{RTreeObject, AABB};
 
+use crate::elements::element::Transform;
+use crate::Affine;
 use crate::Element;
+use crate::FloatPoint;
 use crate::Point;
+use crate::Polygon;
 
-#[derive(Debug)]
+/// An axis aligned rectangle.
+///
+/// Like `Line`, the corners keep their sub-unit remainder so that repeated transformations do not
+/// accumulate rounding errors.
+#[derive(Debug, Clone)]
 pub struct Rectangle {
     lower: Point,
     upper: Point,
+    /// The sub-unit remainders of `lower` and `upper`, `None` if both are exact.
+    remainders: Option<(FloatPoint, FloatPoint)>,
+}
+
+impl PartialEq for Rectangle {
+    fn eq(&self, other: &Self) -> bool {
+        self.lower == other.lower
+            && self.upper == other.upper
+            && self.remainders.unwrap_or_default() == other.remainders.unwrap_or_default()
+    }
+}
+
+// The remainders are never NaN, `from_float_corners` clamps non-finite corners.
+impl Eq for Rectangle {}
+
+impl Hash for Rectangle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.lower.hash(state);
+        self.upper.hash(state);
+    }
 }
 
 impl Rectangle {
@@ -31,6 +60,24 @@ impl Rectangle {
         Rectangle {
             lower: real_lower,
             upper: real_upper,
+            remainders: None,
+        }
+    }
+
+    /// Creates a rectangle with sub-unit precision.
+    ///
+    /// Non-finite coordinates are clamped, see `FloatPoint::split`.
+    pub fn from_float_corners(lower: FloatPoint, upper: FloatPoint) -> Self {
+        let real_lower = FloatPoint::new(lower.x.min(upper.x), lower.y.min(upper.y));
+        let real_upper = FloatPoint::new(lower.x.max(upper.x), lower.y.max(upper.y));
+        let (lower, lower_remainder) = real_lower.split();
+        let (upper, upper_remainder) = real_upper.split();
+        let exact =
+            lower_remainder == FloatPoint::default() && upper_remainder == FloatPoint::default();
+        Rectangle {
+            lower,
+            upper,
+            remainders: (!exact).then_some((lower_remainder, upper_remainder)),
         }
     }
 
@@ -41,6 +88,18 @@ impl Rectangle {
     pub fn upper(&self) -> Point {
         self.upper
     }
+
+    /// The lower corner with its sub-unit remainder.
+    pub fn lower_float(&self) -> FloatPoint {
+        let remainder = self.remainders.map(|(lower, _)| lower).unwrap_or_default();
+        FloatPoint::from_parts(self.lower, remainder)
+    }
+
+    /// The upper corner with its sub-unit remainder.
+    pub fn upper_float(&self) -> FloatPoint {
+        let remainder = self.remainders.map(|(_, upper)| upper).unwrap_or_default();
+        FloatPoint::from_parts(self.upper, remainder)
+    }
 }
 
 impl RTreeObject for Rectangle {
@@ -59,6 +118,37 @@ impl Element for Rectangle {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+impl Transform for Rectangle {
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        let (lower, upper) = (self.lower_float(), self.upper_float());
+        if !affine.preserves_axes() {
+            let corners = [
+                lower,
+                FloatPoint::new(upper.x, lower.y),
+                upper,
+                FloatPoint::new(lower.x, upper.y),
+            ];
+            return Some(Box::new(Polygon::from_iter(
+                corners.map(|corner| affine.apply_float(corner).round()),
+            )));
+        }
+        *self = Self::from_float_corners(affine.apply_float(lower), affine.apply_float(upper));
+        None
+    }
 }
 
 #[cfg(test)]
@@ -122,4 +212,49 @@ mod tests {
         let rectangle_ref = any.downcast_mut::<Rectangle>().unwrap();
         assert_eq!(rectangle_ref.lower(), (1, 0));
     }
+
+    #[test]
+    fn axis_preserving_transform_keeps_rectangle() {
+        let mut rectangle = Rectangle::new((1, 1).into(), (2, 3).into());
+        assert!(rectangle.transform(&Affine::scale(-2.0, 1.0)).is_none());
+        assert_eq!(rectangle.lower(), (-4, 1));
+        assert_eq!(rectangle.upper(), (-2, 3));
+    }
+
+    #[test]
+    fn keeps_sub_unit_precision_across_transforms() {
+        let mut rectangle = Rectangle::new((1, 1).into(), (3, 5).into());
+        let original = rectangle.clone();
+        assert!(rectangle.transform(&Affine::scale(0.5, 0.5)).is_none());
+        assert_eq!(rectangle.lower_float(), FloatPoint::new(0.5, 0.5));
+        assert_eq!(rectangle.upper(), (2, 3));
+        assert!(rectangle.transform(&Affine::scale(2.0, 2.0)).is_none());
+        assert_eq!(rectangle, original);
+
+        let rectangle = Rectangle::from_float_corners((2.5, 0.0).into(), (0.25, 1.0).into());
+        assert_eq!(rectangle.lower(), (0, 0));
+        assert_eq!(rectangle.upper_float(), FloatPoint::new(2.5, 1.0));
+    }
+
+    #[test]
+    fn clamps_non_finite_corners() {
+        let rectangle =
+            Rectangle::from_float_corners((f64::NAN, 0.0).into(), (1.0, f64::INFINITY).into());
+        assert_eq!(rectangle, rectangle.clone());
+        assert_eq!(rectangle.upper(), (1, i128::MAX));
+        assert_eq!(
+            Rectangle::from_float_corners((0.0, 0.0).into(), (1.0, 2.0).into()),
+            Rectangle::new((0, 0).into(), (1, 2).into())
+        );
+    }
+
+    #[test]
+    fn rotation_turns_rectangle_into_polygon() {
+        let mut rectangle = Rectangle::new((0, 0).into(), (2, 1).into());
+        let replacement = rectangle
+            .transform(&Affine::rotate(std::f64::consts::FRAC_PI_2))
+            .unwrap();
+        let polygon = replacement.as_any().downcast_ref::<Polygon>().unwrap();
+        assert!(polygon.iter().eq([(0, 0), (0, 2), (-1, 2), (-1, 0)].iter()));
+    }
 }
@@ -0,0 +1,217 @@
+use std::any::Any;
+use std::cmp::{max, min};
+use std::slice::Iter;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Color;
+use crate::Element;
+use crate::Line;
+use crate::Point;
+use crate::Text;
+use crate::Vector;
+use crate::MODEL_UNITS_PER_MILLIMETRE;
+
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum NoteContent {
+    Text(Text),
+    Ink(Line),
+}
+
+/// A colored box with its own text and ink content.
+///
+/// The coordinates of the content are relative to the lower corner of the note, so the content
+/// moves along with it. A collapsed note only covers a square of `COLLAPSED_SIZE` at its lower
+/// corner, or less along an axis where the expanded note is smaller.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct StickyNote {
+    lower: Point,
+    upper: Point,
+    color: Color,
+    collapsed: bool,
+    content: Vec<NoteContent>,
+}
+
+impl StickyNote {
+    /// The edge length of a collapsed note in model units, 5 mm.
+    pub const COLLAPSED_SIZE: i128 = 5 * MODEL_UNITS_PER_MILLIMETRE;
+
+    pub fn new(lower: Point, upper: Point, color: Color) -> Self {
+        StickyNote {
+            lower: Point {
+                x: min(lower.x, upper.x),
+                y: min(lower.y, upper.y),
+            },
+            upper: Point {
+                x: max(lower.x, upper.x),
+                y: max(lower.y, upper.y),
+            },
+            color,
+            collapsed: false,
+            content: Vec::new(),
+        }
+    }
+
+    pub fn lower(&self) -> Point {
+        self.lower
+    }
+
+    pub fn upper(&self) -> Point {
+        self.upper
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    pub fn toggle(&mut self) {
+        self.collapsed = !self.collapsed;
+    }
+
+    /// Adds content with coordinates relative to the lower corner of the note.
+    pub fn push(&mut self, content: NoteContent) {
+        self.content.push(content);
+    }
+
+    pub fn iter(&self) -> Iter<'_, NoteContent> {
+        self.content.iter()
+    }
+
+    /// Converts a position relative to the note into page coordinates.
+    pub fn to_page(&self, relative: Point) -> Point {
+        self.lower + relative.to_vector()
+    }
+
+    pub fn move_to(&mut self, lower: Point) {
+        self.upper = lower + (self.upper - self.lower);
+        self.lower = lower;
+    }
+}
+
+impl RTreeObject for StickyNote {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        if self.collapsed {
+            let size = self.upper - self.lower;
+            return AABB::from_corners(
+                self.lower,
+                self.lower
+                    + Vector::new(
+                        min(size.x, Self::COLLAPSED_SIZE),
+                        min(size.y, Self::COLLAPSED_SIZE),
+                    ),
+            );
+        }
+        AABB::from_corners(self.lower, self.upper)
+    }
+}
+
+impl Element for StickyNote {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+/// Only translations are supported, the note and its content stay axis aligned.
+impl Transform for StickyNote {
+    fn supports(&self, affine: &Affine) -> bool {
+        affine.is_translation()
+    }
+
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        self.move_to(affine.apply(self.lower));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note() -> StickyNote {
+        StickyNote::new((10, 10).into(), (110, 60).into(), Color::rgb(255, 255, 0))
+    }
+
+    #[test]
+    fn expanded_envelope_covers_note() {
+        let note = note();
+        assert_eq!(note.envelope().lower(), (10, 10));
+        assert_eq!(note.envelope().upper(), (110, 60));
+    }
+
+    #[test]
+    fn collapsed_envelope_is_small() {
+        let mut note = StickyNote::new(
+            (10, 10).into(),
+            (20010, 30010).into(),
+            Color::rgb(255, 255, 0),
+        );
+        note.toggle();
+        assert!(note.is_collapsed());
+        assert_eq!(note.envelope().lower(), (10, 10));
+        assert_eq!(note.envelope().upper(), (5010, 5010));
+    }
+
+    #[test]
+    fn collapsed_envelope_never_exceeds_expanded_note() {
+        let mut note = note();
+        note.set_collapsed(true);
+        assert_eq!(note.envelope().upper(), (110, 60));
+
+        let mut wide = StickyNote::new((0, 0).into(), (20000, 100).into(), Color::rgb(255, 255, 0));
+        wide.set_collapsed(true);
+        assert_eq!(wide.envelope().upper(), (5000, 100));
+    }
+
+    #[test]
+    fn content_moves_with_note() {
+        let mut note = note();
+        note.push(NoteContent::Text(Text::new((5, 5).into(), "todo")));
+        note.push(NoteContent::Ink(Line::from_iter([(1, 1), (2, 2)].iter())));
+        assert_eq!(note.to_page((5, 5).into()), (15, 15));
+
+        note.move_to((0, 0).into());
+
+        assert_eq!(note.upper(), (100, 50));
+        assert_eq!(note.to_page((5, 5).into()), (5, 5));
+        assert_eq!(note.iter().count(), 2);
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let note = note();
+        let any = note.as_any();
+        assert!(any.downcast_ref::<StickyNote>().is_some());
+    }
+}
@@ -0,0 +1,106 @@
+use std::any::Any;
+
+use rstar::{RTreeObject, AABB};
+
+use crate::elements::element::Transform;
+use crate::Affine;
+use crate::Element;
+use crate::Point;
+
+/// A piece of text anchored at a position.
+///
+/// The model has no knowledge of fonts, so the envelope only covers the anchor position.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Text {
+    position: Point,
+    content: String,
+}
+
+impl Text {
+    pub fn new(position: Point, content: impl Into<String>) -> Self {
+        Text {
+            position,
+            content: content.into(),
+        }
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+    }
+}
+
+impl RTreeObject for Text {
+    type Envelope = AABB<Point>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point(self.position)
+    }
+}
+
+impl Element for Text {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Element> {
+        Box::new(self.clone())
+    }
+
+    fn as_transform(&self) -> Option<&dyn Transform> {
+        Some(self)
+    }
+
+    fn as_transform_mut(&mut self) -> Option<&mut dyn Transform> {
+        Some(self)
+    }
+}
+
+/// Only translations are supported, the model cannot scale or rotate glyphs.
+impl Transform for Text {
+    fn supports(&self, affine: &Affine) -> bool {
+        affine.is_translation()
+    }
+
+    fn transform(&mut self, affine: &Affine) -> Option<Box<dyn Element>> {
+        self.position = affine.apply(self.position);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correct_envelope() {
+        let text = Text::new((3, 4).into(), "hello");
+        assert_eq!(text.envelope().lower(), (3, 4));
+        assert_eq!(text.envelope().upper(), (3, 4));
+    }
+
+    #[test]
+    fn can_change_content() {
+        let mut text = Text::new((0, 0).into(), "hello");
+        text.set_content("world");
+        assert_eq!(text.content(), "world");
+    }
+
+    #[test]
+    fn can_cast_to_any() {
+        let text = Text::new((0, 0).into(), "hello");
+        let any = text.as_any();
+        assert_eq!(any.downcast_ref::<Text>().unwrap().content(), "hello");
+    }
+}
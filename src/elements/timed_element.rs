@@ -1,13 +1,22 @@
-use crate::{Element, Point};
+use crate::{Affine, AuthorId, Element, ElementId, LayerId, Point};
 use rstar::{RTreeObject, AABB};
 use std::any::Any;
 use std::cmp::Ordering;
 use std::ops::{Deref, DerefMut};
+use std::time::SystemTime;
 
 #[derive(Debug)]
 pub struct TimedElement {
+    id: ElementId,
     z_index: i32,
     t_index: u128,
+    revision: u128,
+    layer: LayerId,
+    locked: bool,
+    hidden: bool,
+    created: Option<SystemTime>,
+    modified: Option<SystemTime>,
+    author: Option<AuthorId>,
     element: Box<dyn Element>,
 }
 
@@ -17,12 +26,24 @@ impl TimedElement {
         T: Element + 'static,
     {
         TimedElement {
+            id: ElementId::new(t_index),
             z_index,
             t_index,
+            revision: t_index,
+            layer: LayerId::default(),
+            locked: false,
+            hidden: false,
+            created: None,
+            modified: None,
+            author: None,
             element: Box::new(element),
         }
     }
 
+    pub fn id(&self) -> ElementId {
+        self.id
+    }
+
     pub fn z_index(&self) -> i32 {
         self.z_index
     }
@@ -30,6 +51,114 @@ impl TimedElement {
     pub fn t_index(&self) -> u128 {
         self.t_index
     }
+
+    /// The `t_index` of the write that gave the element its current content.
+    ///
+    /// Equals `t_index` unless the element was replaced or restored from a group, in which case
+    /// its previous state is kept as a tombstone.
+    pub fn revision(&self) -> u128 {
+        self.revision
+    }
+
+    pub(crate) fn set_revision(&mut self, revision: u128) {
+        self.revision = revision;
+    }
+
+    pub fn layer(&self) -> LayerId {
+        self.layer
+    }
+
+    pub(crate) fn set_layer(&mut self, layer: LayerId) {
+        self.layer = layer;
+    }
+
+    /// Locked elements are skipped by selections, hit tests and erasers unless requested.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub(crate) fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    /// Hidden elements are not painted and skipped like locked elements.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub(crate) fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
+
+    /// The wall-clock time the element was inserted into a page.
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created
+    }
+
+    /// The wall-clock time the element was last changed through a page.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    pub fn author(&self) -> Option<&AuthorId> {
+        self.author.as_ref()
+    }
+
+    pub(crate) fn set_created(&mut self, time: SystemTime, author: Option<AuthorId>) {
+        self.created = Some(time);
+        self.modified = Some(time);
+        self.author = author;
+    }
+
+    pub(crate) fn set_modified(&mut self, time: SystemTime) {
+        self.modified = Some(time);
+    }
+
+    pub(crate) fn replace_element(&mut self, element: Box<dyn Element>) {
+        self.element = element;
+    }
+
+    /// Whether `transform` would apply the transformation.
+    pub fn supports_transform(&self, affine: &Affine) -> bool {
+        self.element
+            .as_transform()
+            .is_some_and(|transform| transform.supports(affine))
+    }
+
+    /// Applies an affine transformation to the element.
+    ///
+    /// Returns `false` and leaves the element unchanged if the element does not support the
+    /// transformation, see `Transform::supports`.
+    pub fn transform(&mut self, affine: &Affine) -> bool {
+        let Some(transform) = self.element.as_transform_mut() else {
+            return false;
+        };
+        if !transform.supports(affine) {
+            return false;
+        }
+        if let Some(replacement) = transform.transform(affine) {
+            self.element = replacement;
+        }
+        true
+    }
+}
+
+impl Clone for TimedElement {
+    fn clone(&self) -> Self {
+        TimedElement {
+            id: self.id,
+            z_index: self.z_index,
+            t_index: self.t_index,
+            revision: self.revision,
+            layer: self.layer,
+            locked: self.locked,
+            hidden: self.hidden,
+            created: self.created,
+            modified: self.modified,
+            author: self.author.clone(),
+            element: self.element.clone_box(),
+        }
+    }
 }
 
 impl Deref for TimedElement {
@@ -54,24 +183,27 @@ impl RTreeObject for TimedElement {
     }
 }
 
+/// Elements are ordered as they are painted: by `z_index`, then by `t_index` and finally by id.
 impl PartialEq for TimedElement {
     fn eq(&self, other: &Self) -> bool {
-        self.t_index() == other.t_index() && self.z_index() == other.z_index()
+        self.cmp(other) == Ordering::Equal
     }
 }
 
+impl Eq for TimedElement {}
+
 impl PartialOrd for TimedElement {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.z_index() < other.z_index() {
-            return Some(Ordering::Less);
-        }
-        if self.t_index() < other.t_index() {
-            return Some(Ordering::Less);
-        }
-        if self.z_index() == other.z_index() && self.t_index() == other.t_index() {
-            return Some(Ordering::Equal);
-        }
-        Some(Ordering::Greater)
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimedElement {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.z_index
+            .cmp(&other.z_index)
+            .then(self.t_index.cmp(&other.t_index))
+            .then(self.id.cmp(&other.id))
     }
 }
 
@@ -80,6 +212,32 @@ mod tests {
     use super::*;
     use crate::elements::element::mocks::MockElement;
 
+    #[test]
+    fn id_is_derived_from_t_index() {
+        let element = TimedElement::new(MockElement::new(), 0, 7);
+        assert_eq!(element.id(), ElementId::new(7));
+    }
+
+    #[test]
+    fn has_no_timestamps_or_author_by_default() {
+        let element = TimedElement::new(MockElement::new(), 0, 0);
+        assert!(element.created().is_none());
+        assert!(element.modified().is_none());
+        assert!(element.author().is_none());
+    }
+
+    #[test]
+    fn modifying_keeps_creation_time() {
+        let created = SystemTime::UNIX_EPOCH;
+        let modified = created + std::time::Duration::from_secs(5);
+        let mut element = TimedElement::new(MockElement::new(), 0, 0);
+        element.set_created(created, Some(AuthorId::new("alice")));
+        element.set_modified(modified);
+        assert_eq!(element.created(), Some(created));
+        assert_eq!(element.modified(), Some(modified));
+        assert_eq!(element.author(), Some(&AuthorId::new("alice")));
+    }
+
     #[test]
     fn correct_z_index() {
         let z_index = 5;
@@ -149,6 +307,56 @@ mod tests {
         assert!(elem_1 >= elem_2);
     }
 
+    #[test]
+    fn elements_with_higher_z_index_are_sorted_greater_regardless_of_t_index() {
+        let elem_lower = TimedElement::new(MockElement::new(), 0, 1);
+        let elem_higher = TimedElement::new(MockElement::new(), 1, 0);
+        assert!(elem_lower < elem_higher);
+        assert!(elem_higher > elem_lower);
+        assert_eq!(elem_higher.cmp(&elem_lower), Ordering::Greater);
+    }
+
+    #[test]
+    fn elements_with_same_indices_are_sorted_by_id() {
+        let lhs = TimedElement::new(MockElement::new(), 0, 0);
+        let mut rhs = TimedElement::new(MockElement::new(), 0, 0);
+        rhs.id = ElementId::new(1);
+        assert_ne!(lhs, rhs);
+        assert!(lhs < rhs);
+        assert_eq!(rhs.cmp(&lhs), Ordering::Greater);
+    }
+
+    #[test]
+    fn transform_is_rejected_by_elements_without_support() {
+        let mut element = TimedElement::new(MockElement::new(), 0, 0);
+        assert!(!element.transform(&Affine::identity()));
+    }
+
+    #[test]
+    fn transform_replaces_element_if_required() {
+        let mut element =
+            TimedElement::new(crate::Rectangle::new((0, 0).into(), (2, 2).into()), 0, 0);
+        assert!(element.transform(&Affine::rotate(0.5)));
+        assert!(element.downcast_ref::<crate::Polygon>().is_some());
+    }
+
+    #[test]
+    fn clone_keeps_indices_and_element() {
+        let element = TimedElement::new(crate::Line::from_iter([(1, 2)].iter()), 3, 4);
+        let clone = element.clone();
+        assert_eq!(clone.id(), element.id());
+        assert_eq!(clone.z_index(), 3);
+        assert_eq!(clone.t_index(), 4);
+        assert_eq!(clone.layer(), element.layer());
+        assert!(!clone.is_locked());
+        assert!(!clone.is_hidden());
+        assert!(clone
+            .downcast_ref::<crate::Line>()
+            .unwrap()
+            .iter()
+            .eq([(1, 2)].iter()));
+    }
+
     #[test]
     fn can_deref() {
         let mut mock = MockElement::new();
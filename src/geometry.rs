@@ -0,0 +1,140 @@
+use rstar::AABB;
+
+use crate::{Length, Point, Unit};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Paper {
+    A4,
+    A5,
+    Letter,
+}
+
+impl Paper {
+    /// The width and height in portrait orientation.
+    pub fn size(&self) -> (f64, f64, Unit) {
+        match self {
+            Paper::A4 => (210.0, 297.0, Unit::Millimetre),
+            Paper::A5 => (148.0, 210.0, Unit::Millimetre),
+            Paper::Letter => (8.5, 11.0, Unit::Inch),
+        }
+    }
+}
+
+/// The size of a page.
+///
+/// Fixed pages span from the origin to their width and height in model coordinates.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum PageGeometry {
+    #[default]
+    Infinite,
+    Fixed {
+        width: f64,
+        height: f64,
+        unit: Unit,
+    },
+}
+
+impl PageGeometry {
+    pub fn fixed(width: f64, height: f64, unit: Unit) -> Self {
+        PageGeometry::Fixed {
+            width,
+            height,
+            unit,
+        }
+    }
+
+    pub fn paper(paper: Paper, orientation: Orientation) -> Self {
+        let (width, height, unit) = paper.size();
+        match orientation {
+            Orientation::Portrait => Self::fixed(width, height, unit),
+            Orientation::Landscape => Self::fixed(height, width, unit),
+        }
+    }
+
+    /// Returns `None` for infinite pages.
+    pub fn orientation(&self) -> Option<Orientation> {
+        match self {
+            PageGeometry::Infinite => None,
+            PageGeometry::Fixed { width, height, .. } if width > height => {
+                Some(Orientation::Landscape)
+            }
+            PageGeometry::Fixed { .. } => Some(Orientation::Portrait),
+        }
+    }
+
+    /// Swaps width and height if the page does not have the given orientation.
+    pub fn with_orientation(self, orientation: Orientation) -> Self {
+        match self {
+            PageGeometry::Fixed {
+                width,
+                height,
+                unit,
+            } if self.orientation() != Some(orientation) => Self::fixed(height, width, unit),
+            _ => self,
+        }
+    }
+
+    /// The width, or `None` for infinite pages.
+    pub fn width(&self) -> Option<Length> {
+        match self {
+            PageGeometry::Infinite => None,
+            PageGeometry::Fixed { width, unit, .. } => Some(unit.length(*width)),
+        }
+    }
+
+    /// The height, or `None` for infinite pages.
+    pub fn height(&self) -> Option<Length> {
+        match self {
+            PageGeometry::Infinite => None,
+            PageGeometry::Fixed { height, unit, .. } => Some(unit.length(*height)),
+        }
+    }
+
+    /// The area covered by the page in model coordinates, or `None` for infinite pages.
+    pub fn bounds(&self) -> Option<AABB<Point>> {
+        let (width, height) = (self.width()?, self.height()?);
+        Some(AABB::from_corners(
+            Point::new(0, 0),
+            Point::new(width.value(), height.value()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paper_presets_have_portrait_and_landscape_bounds() {
+        let a4 = PageGeometry::paper(Paper::A4, Orientation::Portrait);
+        assert_eq!(a4.orientation(), Some(Orientation::Portrait));
+        assert_eq!(a4.bounds().unwrap().upper(), (210000, 297000));
+
+        let letter = PageGeometry::paper(Paper::Letter, Orientation::Landscape);
+        assert_eq!(letter.orientation(), Some(Orientation::Landscape));
+        assert_eq!(letter.bounds().unwrap().upper(), (279400, 215900));
+        assert_eq!(letter.width(), Some(Length::inches(11.0)));
+    }
+
+    #[test]
+    fn with_orientation_swaps_dimensions() {
+        let a5 = PageGeometry::paper(Paper::A5, Orientation::Portrait);
+        assert_eq!(
+            a5.with_orientation(Orientation::Landscape),
+            PageGeometry::paper(Paper::A5, Orientation::Landscape)
+        );
+        assert_eq!(a5.with_orientation(Orientation::Portrait), a5);
+    }
+
+    #[test]
+    fn infinite_pages_have_no_bounds() {
+        assert!(PageGeometry::Infinite.bounds().is_none());
+        assert!(PageGeometry::Infinite.orientation().is_none());
+    }
+}
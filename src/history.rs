@@ -0,0 +1,102 @@
+use std::slice::Iter;
+
+use rstar::{Envelope, RTreeObject, AABB};
+
+use crate::{Point, TimedElement};
+
+/// A copy of an element that was extracted from a page, or of the state it had before it was
+/// replaced or grouped, kept for time-travel queries.
+#[derive(Debug, Clone)]
+pub struct Tombstone {
+    element: TimedElement,
+    removed_at: u128,
+}
+
+impl Tombstone {
+    pub(crate) fn new(element: TimedElement, removed_at: u128) -> Self {
+        Tombstone {
+            element,
+            removed_at,
+        }
+    }
+
+    pub fn element(&self) -> &TimedElement {
+        &self.element
+    }
+
+    /// The `t_index` of the page at the time the element was extracted or replaced.
+    pub fn removed_at(&self) -> u128 {
+        self.removed_at
+    }
+
+    pub fn was_present_at(&self, t_index: u128) -> bool {
+        self.element.revision() <= t_index && t_index < self.removed_at
+    }
+}
+
+/// The elements of a page as they were present at a given `t_index`.
+pub struct Snapshot<'a> {
+    t_index: u128,
+    elements: Vec<&'a TimedElement>,
+}
+
+impl<'a> Snapshot<'a> {
+    pub(crate) fn new(t_index: u128, mut elements: Vec<&'a TimedElement>) -> Self {
+        elements.sort_by_key(|element| (element.revision(), element.t_index()));
+        Snapshot { t_index, elements }
+    }
+
+    pub fn t_index(&self) -> u128 {
+        self.t_index
+    }
+
+    /// Iterates over the elements in the order they were written.
+    pub fn iter(&self) -> std::iter::Copied<Iter<'_, &'a TimedElement>> {
+        self.elements.iter().copied()
+    }
+
+    pub fn locate_in_envelope(
+        &self,
+        envelope: AABB<Point>,
+    ) -> impl Iterator<Item = &'a TimedElement> + '_ {
+        self.iter()
+            .filter(move |element| envelope.contains_envelope(&element.envelope()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::element::mocks::MockElement;
+    use crate::Line;
+
+    #[test]
+    fn tombstone_knows_when_element_was_present() {
+        let tombstone = Tombstone::new(TimedElement::new(MockElement::new(), 0, 2), 5);
+        assert!(!tombstone.was_present_at(1));
+        assert!(tombstone.was_present_at(2));
+        assert!(tombstone.was_present_at(4));
+        assert!(!tombstone.was_present_at(5));
+    }
+
+    #[test]
+    fn snapshot_is_ordered_by_t_index() {
+        let first = TimedElement::new(Line::from_iter([(0, 0)].iter()), 5, 1);
+        let second = TimedElement::new(Line::from_iter([(9, 9)].iter()), 0, 2);
+        let snapshot = Snapshot::new(2, vec![&second, &first]);
+        assert_eq!(snapshot.len(), 2);
+        assert!(snapshot.iter().map(|element| element.t_index()).eq([1, 2]));
+        let located: Vec<_> = snapshot
+            .locate_in_envelope(AABB::from_corners((0, 0).into(), (1, 1).into()))
+            .collect();
+        assert_eq!(located, vec![&first]);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::TimedElement;
+use crate::{Page, TimedElement};
+use std::slice::{Iter, IterMut};
 
 pub struct SelectionIter<'a> {
     iter: Box<dyn Iterator<Item = &'a TimedElement> + 'a>,
@@ -18,7 +19,68 @@ impl<'a> Iterator for SelectionIter<'a> {
     }
 }
 
+pub struct PageIter<'a> {
+    iter: Iter<'a, Box<Page>>,
+}
+
+impl<'a> PageIter<'a> {
+    pub(crate) fn new(iter: Iter<'a, Box<Page>>) -> Self {
+        PageIter { iter }
+    }
+}
+
+impl<'a> Iterator for PageIter<'a> {
+    type Item = &'a Page;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|page| page.as_ref())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PageIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|page| page.as_ref())
+    }
+}
+
+impl ExactSizeIterator for PageIter<'_> {}
+
+pub struct PageIterMut<'a> {
+    iter: IterMut<'a, Box<Page>>,
+}
+
+impl<'a> PageIterMut<'a> {
+    pub(crate) fn new(iter: IterMut<'a, Box<Page>>) -> Self {
+        PageIterMut { iter }
+    }
+}
+
+impl<'a> Iterator for PageIterMut<'a> {
+    type Item = &'a mut Page;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|page| page.as_mut())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PageIterMut<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|page| page.as_mut())
+    }
+}
+
+impl ExactSizeIterator for PageIterMut<'_> {}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
     use crate::elements::element::mocks::MockElement;
@@ -33,4 +95,15 @@ mod tests {
         assert_eq!(element_ref, Some(&timed_element));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn page_iter_unboxes_pages_in_both_directions() {
+        let pages = [Box::new(Page::default()), Box::new(Page::default())];
+        let ids: Vec<_> = PageIter::new(pages.iter())
+            .rev()
+            .map(|page| page.id())
+            .collect();
+        assert_eq!(ids, vec![pages[1].id(), pages[0].id()]);
+        assert_eq!(PageIter::new(pages.iter()).len(), 2);
+    }
 }
@@ -1,28 +1,427 @@
-use crate::Page;
+use crate::{
+    AuthorId, Bookmark, BookmarkId, Clock, Color, ElementId, JournalMetadata, Link, LinkTarget,
+    Page, PageId, Point, ResolvedBookmark, Section, SectionId, SystemClock, TimedElement, TocEntry,
+};
 
-use std::collections::linked_list::{Iter, IterMut};
-use std::collections::LinkedList;
+use crate::iterator::{PageIter, PageIterMut};
+use crate::section;
+use rstar::{RTreeObject, AABB};
+use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::sync::Arc;
+use std::time::SystemTime;
 
+#[derive(Debug)]
+pub enum ResolvedLink<'a> {
+    Uri(&'a str),
+    Page {
+        index: usize,
+        page: &'a Page,
+    },
+    Element {
+        index: usize,
+        page: &'a Page,
+        element: &'a TimedElement,
+    },
+}
+
+/// An ordered collection of pages.
+///
+/// Pages are boxed so that inserting and removing only moves pointers.
 pub struct Journal {
-    pages: LinkedList<Page>,
+    #[allow(clippy::vec_box)]
+    pages: Vec<Box<Page>>,
+    metadata: JournalMetadata,
+    sections: Vec<Section>,
+    next_section_id: u64,
+    bookmarks: Vec<Bookmark>,
+    next_bookmark_id: u64,
+    clock: Arc<dyn Clock>,
 }
 
 impl Journal {
-    pub fn insert(&mut self, at: usize, page: Page) {
-        let mut tail = self.pages.split_off(at);
-        self.pages.push_back(page);
-        self.pages.append(&mut tail);
+    /// Creates a journal with one empty page, taking timestamps from the given clock.
+    ///
+    /// Pages added later are switched to the same clock.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Journal {
+            pages: vec![Box::new(Page::with_clock(clock.clone()))],
+            metadata: JournalMetadata::new(clock.now()),
+            sections: Vec::new(),
+            next_section_id: 0,
+            bookmarks: Vec::new(),
+            next_bookmark_id: 0,
+            clock,
+        }
+    }
+
+    pub fn metadata(&self) -> &JournalMetadata {
+        &self.metadata
+    }
+
+    /// Replaces the metadata, e.g. with metadata loaded together with the pages.
+    ///
+    /// Unlike `update_metadata` this keeps the modification time of the given metadata.
+    pub fn set_metadata(&mut self, metadata: JournalMetadata) {
+        self.metadata = metadata;
+    }
+
+    /// Modifies the metadata and updates its modification time.
+    pub fn update_metadata<F: FnOnce(&mut JournalMetadata)>(&mut self, modify: F) {
+        modify(&mut self.metadata);
+        self.touch();
+    }
+
+    /// The time of the last change to the metadata, the order of pages or any page.
+    pub fn modified(&self) -> SystemTime {
+        self.iter()
+            .map(|page| page.modified())
+            .fold(self.metadata.modified(), SystemTime::max)
+    }
+
+    /// Renders the metadata as an SVG `<metadata>` element, see `JournalMetadata::to_svg`.
+    pub fn svg_metadata(&self) -> String {
+        self.metadata.to_svg(self.modified())
+    }
+
+    /// Returns the metadata as PDF document information, see `JournalMetadata::to_pdf_info`.
+    ///
+    /// The values are not escaped for PDF strings.
+    pub fn pdf_info(&self) -> Vec<(String, String)> {
+        self.metadata.to_pdf_info(self.modified())
     }
 
-    pub fn push_back(&mut self, page: Page) {
-        self.pages.push_back(page);
+    fn touch(&mut self) {
+        let now = self.clock.now();
+        self.metadata.set_modified(now);
+    }
+
+    /// Inserts a page at the given position.
+    ///
+    /// The page takes its timestamps from the clock of the journal from now on. Panics if
+    /// `at > len`.
+    pub fn insert(&mut self, at: usize, mut page: Page) {
+        page.set_clock(self.clock.clone());
+        self.pages.insert(at, Box::new(page));
+        section::page_inserted(&mut self.sections, at);
+        self.touch();
+    }
+
+    /// Appends a page, see `Journal::insert`.
+    pub fn push_back(&mut self, mut page: Page) {
+        page.set_clock(self.clock.clone());
+        self.pages.push(Box::new(page));
+        self.touch();
     }
 
     pub fn remove(&mut self, at: usize) -> Option<Page> {
-        let mut tail = self.pages.split_off(at);
-        let page = tail.pop_front();
-        self.pages.append(&mut tail);
-        page
+        if at >= self.pages.len() {
+            return None;
+        }
+        section::page_removed(&mut self.sections, at);
+        self.touch();
+        Some(*self.pages.remove(at))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Page> {
+        self.pages.get(index).map(|page| page.as_ref())
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Page> {
+        self.pages.get_mut(index).map(|page| page.as_mut())
+    }
+
+    /// Iterates over the pages in the given range, or returns `None` if it is out of bounds.
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> Option<PageIter<'_>> {
+        let bounds: (Bound<usize>, Bound<usize>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        self.pages
+            .get(bounds)
+            .map(|pages| PageIter::new(pages.iter()))
+    }
+
+    /// Like `range`, but yields mutable pages.
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> Option<PageIterMut<'_>> {
+        let bounds: (Bound<usize>, Bound<usize>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        self.pages
+            .get_mut(bounds)
+            .map(|pages| PageIterMut::new(pages.iter_mut()))
+    }
+
+    pub fn page_by_id(&self, id: PageId) -> Option<&Page> {
+        self.get(self.index_of(id)?)
+    }
+
+    pub fn page_by_id_mut(&mut self, id: PageId) -> Option<&mut Page> {
+        let index = self.index_of(id)?;
+        self.get_mut(index)
+    }
+
+    /// Returns the current position of the page with the given id.
+    pub fn index_of(&self, id: PageId) -> Option<usize> {
+        self.iter().position(|page| page.id() == id)
+    }
+
+    /// Moves the page at `from` so that it ends up at position `to`.
+    ///
+    /// Returns `false` if either position is out of bounds.
+    pub fn move_page(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.len() || to >= self.len() {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+        let page = self.pages.remove(from);
+        self.pages.insert(to, page);
+        section::page_moved(&mut self.sections, from, to);
+        self.touch();
+        true
+    }
+
+    /// Adds a section covering the given pages.
+    ///
+    /// The section is nested into the smallest section containing it and sections it contains
+    /// become its children. Returns `None` if the range is empty, out of bounds or partially
+    /// overlaps another section.
+    pub fn add_section(
+        &mut self,
+        title: impl Into<String>,
+        pages: RangeInclusive<usize>,
+    ) -> Option<SectionId> {
+        if pages.is_empty() || *pages.end() >= self.len() {
+            return None;
+        }
+        let id = SectionId::new(self.next_section_id);
+        section::insert(&mut self.sections, Section::new(id, title.into(), pages)).ok()?;
+        self.next_section_id += 1;
+        self.touch();
+        Some(id)
+    }
+
+    /// Removes a section, its subsections take its place.
+    pub fn remove_section(&mut self, id: SectionId) -> bool {
+        let removed = section::remove(&mut self.sections, id);
+        if removed {
+            self.touch();
+        }
+        removed
+    }
+
+    pub fn rename_section(&mut self, id: SectionId, title: impl Into<String>) -> bool {
+        let Some(section) = section::find_mut(&mut self.sections, id) else {
+            return false;
+        };
+        section.set_title(title.into());
+        self.touch();
+        true
+    }
+
+    pub fn section(&self, id: SectionId) -> Option<&Section> {
+        section::find(&self.sections, id)
+    }
+
+    /// The top-level sections ordered by their first page.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Lists all sections depth-first in page order.
+    pub fn table_of_contents(&self) -> Vec<TocEntry> {
+        let mut entries = Vec::new();
+        section::table_of_contents(&self.sections, 0, &mut entries);
+        entries
+    }
+
+    /// Iterates over the pages with the given tag.
+    pub fn pages_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Page> {
+        self.iter().filter(move |page| page.has_tag(tag))
+    }
+
+    /// Bookmarks a page or an area of it.
+    ///
+    /// Returns `None` if the page is not part of this journal.
+    pub fn add_bookmark(
+        &mut self,
+        label: impl Into<String>,
+        color: Color,
+        page: PageId,
+        viewport: Option<AABB<Point>>,
+    ) -> Option<BookmarkId> {
+        self.index_of(page)?;
+        Some(self.push_bookmark(label.into(), color, page, viewport, None))
+    }
+
+    /// Bookmarks an element, remembering its current area.
+    ///
+    /// Returns `None` if the element is not on the given page of this journal.
+    pub fn add_element_bookmark(
+        &mut self,
+        label: impl Into<String>,
+        color: Color,
+        page: PageId,
+        element: ElementId,
+    ) -> Option<BookmarkId> {
+        let viewport = self.page_by_id(page)?.get(element)?.envelope();
+        Some(self.push_bookmark(label.into(), color, page, Some(viewport), Some(element)))
+    }
+
+    fn push_bookmark(
+        &mut self,
+        label: String,
+        color: Color,
+        page: PageId,
+        viewport: Option<AABB<Point>>,
+        element: Option<ElementId>,
+    ) -> BookmarkId {
+        let id = BookmarkId::new(self.next_bookmark_id);
+        self.next_bookmark_id += 1;
+        self.bookmarks
+            .push(Bookmark::new(id, label, color, page, viewport, element));
+        self.touch();
+        id
+    }
+
+    pub fn remove_bookmark(&mut self, id: BookmarkId) -> Option<Bookmark> {
+        let position = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.id() == id)?;
+        self.touch();
+        Some(self.bookmarks.remove(position))
+    }
+
+    pub fn bookmark(&self, id: BookmarkId) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.id() == id)
+    }
+
+    pub fn bookmark_mut(&mut self, id: BookmarkId) -> Option<&mut Bookmark> {
+        self.bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark.id() == id)
+    }
+
+    /// The bookmarks in the order they were added.
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// Iterates over the bookmarks whose label contains the query, ignoring case.
+    pub fn find_bookmarks<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a Bookmark> {
+        self.bookmarks
+            .iter()
+            .filter(move |bookmark| bookmark.matches(query))
+    }
+
+    pub fn bookmarks_on_page(&self, page: PageId) -> impl Iterator<Item = &Bookmark> {
+        self.bookmarks
+            .iter()
+            .filter(move |bookmark| bookmark.page() == page)
+    }
+
+    /// Looks up the current location of a bookmark.
+    ///
+    /// Returns `None` if the bookmark is dangling, see `dangling_bookmarks`.
+    pub fn resolve_bookmark(&self, id: BookmarkId) -> Option<ResolvedBookmark<'_>> {
+        let bookmark = self.bookmark(id)?;
+        let index = self.index_of(bookmark.page())?;
+        ResolvedBookmark::new(bookmark, index, self.get(index)?)
+    }
+
+    /// Finds all bookmarks whose page or element does not exist anymore.
+    ///
+    /// Dangling bookmarks are kept, so they can be shown to the user, until they are removed
+    /// with `remove_bookmark` or `remove_dangling_bookmarks`.
+    pub fn dangling_bookmarks(&self) -> Vec<BookmarkId> {
+        let pages: HashMap<PageId, &Page> = self.iter().map(|page| (page.id(), page)).collect();
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| {
+                !pages.get(&bookmark.page()).is_some_and(|page| {
+                    bookmark
+                        .element()
+                        .is_none_or(|element| page.get(element).is_some())
+                })
+            })
+            .map(|bookmark| bookmark.id())
+            .collect()
+    }
+
+    /// Removes all dangling bookmarks and returns them.
+    pub fn remove_dangling_bookmarks(&mut self) -> Vec<Bookmark> {
+        let dangling = self.dangling_bookmarks();
+        if dangling.is_empty() {
+            return Vec::new();
+        }
+        self.touch();
+        let (removed, kept) = std::mem::take(&mut self.bookmarks)
+            .into_iter()
+            .partition(|bookmark| dangling.contains(&bookmark.id()));
+        self.bookmarks = kept;
+        removed
+    }
+
+    /// Looks up the target of a link.
+    ///
+    /// Returns `None` if the link points to a page or element that is not part of this journal.
+    pub fn resolve_link<'a>(&'a self, link: &'a Link) -> Option<ResolvedLink<'a>> {
+        let page_id = match link.target() {
+            LinkTarget::Uri(uri) => return Some(ResolvedLink::Uri(uri)),
+            LinkTarget::Page(page_id) | LinkTarget::Element(page_id, _) => *page_id,
+        };
+        let index = self.index_of(page_id)?;
+        let page = self.page_by_id(page_id)?;
+        match link.target() {
+            LinkTarget::Element(_, element_id) => Some(ResolvedLink::Element {
+                index,
+                page,
+                element: page.get(*element_id)?,
+            }),
+            _ => Some(ResolvedLink::Page { index, page }),
+        }
+    }
+
+    /// Finds all links whose target page or element does not exist anymore.
+    ///
+    /// Returns the id of the page containing the link and the id of the link itself.
+    pub fn dangling_links(&self) -> Vec<(PageId, ElementId)> {
+        let pages: HashMap<PageId, &Page> = self.iter().map(|page| (page.id(), page)).collect();
+        let resolves = |link: &Link| match link.target() {
+            LinkTarget::Uri(_) => true,
+            LinkTarget::Page(page_id) => pages.contains_key(page_id),
+            LinkTarget::Element(page_id, element_id) => pages
+                .get(page_id)
+                .is_some_and(|page| page.get(*element_id).is_some()),
+        };
+        self.iter()
+            .flat_map(|page| {
+                page.iter()
+                    .filter(|element| {
+                        element
+                            .downcast_ref::<Link>()
+                            .is_some_and(|link| !resolves(link))
+                    })
+                    .map(move |element| (page.id(), element.id()))
+            })
+            .collect()
+    }
+
+    /// Returns all elements created in the half-open interval `[from, to)` with their page.
+    pub fn added_between(&self, from: SystemTime, to: SystemTime) -> Vec<(&Page, &TimedElement)> {
+        self.iter()
+            .flat_map(|page| {
+                page.added_between(from, to)
+                    .map(move |element| (page, element))
+            })
+            .collect()
+    }
+
+    /// Returns all elements of the given author with their page.
+    pub fn by_author(&self, author: &AuthorId) -> Vec<(&Page, &TimedElement)> {
+        self.iter()
+            .flat_map(|page| page.by_author(author).map(move |element| (page, element)))
+            .collect()
     }
 
     pub fn len(&self) -> usize {
@@ -33,47 +432,55 @@ impl Journal {
         self.pages.is_empty()
     }
 
-    pub fn iter(&self) -> Iter<Page> {
+    pub fn iter(&self) -> PageIter<'_> {
         self.into_iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<Page> {
+    pub fn iter_mut(&mut self) -> PageIterMut<'_> {
         self.into_iter()
     }
 }
 
 impl Default for Journal {
     fn default() -> Self {
-        let mut journal = Journal {
-            pages: LinkedList::new(),
-        };
-        journal.pages.push_back(Page::default());
-        journal
+        Journal::with_clock(Arc::new(SystemClock))
     }
 }
 
 impl<'a> IntoIterator for &'a Journal {
     type Item = &'a Page;
-    type IntoIter = Iter<'a, Page>;
+    type IntoIter = PageIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.pages.iter()
+        PageIter::new(self.pages.iter())
     }
 }
 
 impl<'a> IntoIterator for &'a mut Journal {
     type Item = &'a mut Page;
-    type IntoIter = IterMut<'a, Page>;
+    type IntoIter = PageIterMut<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.pages.iter_mut()
+        PageIterMut::new(self.pages.iter_mut())
     }
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::useless_vec,
+    clippy::bool_assert_comparison,
+    clippy::iter_nth_zero
+)]
 mod tests {
     use super::*;
+    use crate::clock::mocks::ManualClock;
     use crate::Line;
+    use rstar::AABB;
+    use std::sync::Arc;
+
+    fn link(target: LinkTarget) -> Link {
+        Link::new(AABB::from_corners((0, 0).into(), (1, 1).into()), target)
+    }
 
     #[test]
     fn has_by_default_one_empty_page() {
@@ -128,4 +535,462 @@ mod tests {
         first_page.insert(Line::from_iter(vec![(0, 1)].iter()), 0);
         assert_eq!(journal.iter().next().unwrap().len(), 1);
     }
+
+    #[test]
+    fn resolves_uri_link() {
+        let journal = Journal::default();
+        let link = link(LinkTarget::Uri("https://example.com".into()));
+        assert!(matches!(
+            journal.resolve_link(&link),
+            Some(ResolvedLink::Uri("https://example.com"))
+        ));
+    }
+
+    #[test]
+    fn resolves_page_link() {
+        let mut journal = Journal::default();
+        let page = Page::default();
+        let page_id = page.id();
+        journal.push_back(page);
+
+        let link = link(LinkTarget::Page(page_id));
+        match journal.resolve_link(&link) {
+            Some(ResolvedLink::Page { index, page }) => {
+                assert_eq!(index, 1);
+                assert_eq!(page.id(), page_id);
+            }
+            other => panic!("unexpected resolution {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolves_element_link() {
+        let mut journal = Journal::default();
+        let mut page = Page::default();
+        let element_id = page.insert(Line::from_iter([(0, 1)].iter()), 0);
+        let page_id = page.id();
+        journal.insert(0, page);
+
+        let link = link(LinkTarget::Element(page_id, element_id));
+        match journal.resolve_link(&link) {
+            Some(ResolvedLink::Element {
+                index,
+                page,
+                element,
+            }) => {
+                assert_eq!(index, 0);
+                assert_eq!(page.id(), page_id);
+                assert_eq!(element.id(), element_id);
+            }
+            other => panic!("unexpected resolution {:?}", other),
+        }
+    }
+
+    #[test]
+    fn does_not_resolve_missing_targets() {
+        let journal = Journal::default();
+        let page_id = journal.iter().next().unwrap().id();
+        assert!(journal
+            .resolve_link(&link(LinkTarget::Page(Page::default().id())))
+            .is_none());
+        assert!(journal
+            .resolve_link(&link(LinkTarget::Element(page_id, ElementId::new(42))))
+            .is_none());
+    }
+
+    #[test]
+    fn detects_dangling_links_after_page_removal() {
+        let mut journal = Journal::default();
+        let target = Page::default();
+        let target_id = target.id();
+        journal.push_back(target);
+        let source_id = journal.iter().next().unwrap().id();
+        let link_id = journal
+            .iter_mut()
+            .next()
+            .unwrap()
+            .insert(link(LinkTarget::Page(target_id)), 0);
+        assert!(journal.dangling_links().is_empty());
+
+        journal.remove(1);
+
+        assert_eq!(journal.dangling_links(), vec![(source_id, link_id)]);
+    }
+
+    #[test]
+    fn finds_elements_across_pages() {
+        let clock = Arc::new(ManualClock::default());
+        let alice = AuthorId::new("alice");
+        let mut journal = Journal::default();
+        journal.push_back(Page::default());
+        let mut ids = Vec::new();
+        for (i, page) in journal.iter_mut().enumerate() {
+            page.set_clock(clock.clone());
+            page.set_author(Some(alice.clone()));
+            clock.set(i as u64 * 10);
+            ids.push((page.id(), page.insert(Line::from_iter([(0, 0)].iter()), 0)));
+        }
+
+        let found = |elements: Vec<(&Page, &TimedElement)>| -> Vec<(PageId, ElementId)> {
+            elements
+                .into_iter()
+                .map(|(page, element)| (page.id(), element.id()))
+                .collect()
+        };
+        assert_eq!(
+            found(journal.added_between(ManualClock::at(5), ManualClock::at(15))),
+            vec![ids[1]]
+        );
+        assert_eq!(found(journal.by_author(&alice)), ids);
+        assert!(journal.by_author(&AuthorId::new("bob")).is_empty());
+    }
+
+    #[test]
+    fn finds_pages_by_id() {
+        let mut journal = Journal::default();
+        let page = Page::default();
+        let id = page.id();
+        journal.push_back(page);
+
+        assert_eq!(journal.index_of(id), Some(1));
+        assert_eq!(journal.page_by_id(id).unwrap().id(), id);
+        journal.page_by_id_mut(id).unwrap().set_title("Notes");
+        assert_eq!(journal.page_by_id(id).unwrap().title(), "Notes");
+        journal.remove(1);
+        assert!(journal.page_by_id(id).is_none());
+        assert!(journal.index_of(id).is_none());
+    }
+
+    #[test]
+    fn moving_pages_keeps_links_intact() {
+        let mut journal = Journal::default();
+        let target = Page::default();
+        let target_id = target.id();
+        journal.push_back(target);
+        journal.push_back(Page::default());
+        let link = link(LinkTarget::Page(target_id));
+
+        assert!(journal.move_page(1, 2));
+        assert_eq!(journal.index_of(target_id), Some(2));
+        assert!(matches!(
+            journal.resolve_link(&link),
+            Some(ResolvedLink::Page { index: 2, .. })
+        ));
+        assert!(journal.move_page(2, 0));
+        assert_eq!(journal.index_of(target_id), Some(0));
+        assert!(!journal.move_page(0, 3));
+    }
+
+    #[test]
+    fn filters_pages_by_tag() {
+        let mut journal = Journal::default();
+        let mut page = Page::default();
+        page.add_tag("physics");
+        let id = page.id();
+        journal.push_back(page);
+        let ids: Vec<PageId> = journal
+            .pages_with_tag("physics")
+            .map(|page| page.id())
+            .collect();
+        assert_eq!(ids, vec![id]);
+    }
+
+    #[test]
+    fn provides_random_access() {
+        let mut journal = Journal::default();
+        let page = Page::default();
+        let id = page.id();
+        journal.push_back(page);
+        assert_eq!(journal.get(1).unwrap().id(), id);
+        journal.get_mut(1).unwrap().set_title("Second");
+        assert_eq!(journal.get(1).unwrap().title(), "Second");
+        assert!(journal.get(2).is_none());
+        assert!(journal.remove(2).is_none());
+    }
+
+    #[test]
+    fn iterates_over_ranges() {
+        let mut journal = Journal::default();
+        for _ in 0..4 {
+            journal.push_back(Page::default());
+        }
+        let ids: Vec<PageId> = journal.iter().map(|page| page.id()).collect();
+        let range: Vec<PageId> = journal.range(1..3).unwrap().map(|page| page.id()).collect();
+        assert_eq!(range, ids[1..3]);
+        assert_eq!(journal.range(3..).unwrap().len(), 2);
+        assert!(journal.range(4..6).is_none());
+
+        for page in journal.range_mut(..=1).unwrap() {
+            page.add_tag("front");
+        }
+        assert_eq!(journal.pages_with_tag("front").count(), 2);
+    }
+
+    #[test]
+    fn builds_table_of_contents() {
+        let mut journal = Journal::default();
+        for _ in 0..5 {
+            journal.push_back(Page::default());
+        }
+        let physics = journal.add_section("Physics", 0..=3).unwrap();
+        let optics = journal.add_section("Optics", 2..=3).unwrap();
+        let mechanics = journal.add_section("Mechanics", 0..=1).unwrap();
+        let maths = journal.add_section("Maths", 4..=5).unwrap();
+        assert!(journal.add_section("Overlap", 3..=4).is_none());
+        assert!(journal.add_section("Outside", 5..=6).is_none());
+        assert!(journal.rename_section(optics, "Light"));
+
+        let toc: Vec<_> = journal
+            .table_of_contents()
+            .into_iter()
+            .map(|entry| (entry.section, entry.title, entry.depth, entry.page))
+            .collect();
+        assert_eq!(
+            toc,
+            vec![
+                (physics, "Physics".to_string(), 0, 0),
+                (mechanics, "Mechanics".to_string(), 1, 0),
+                (optics, "Light".to_string(), 1, 2),
+                (maths, "Maths".to_string(), 0, 4),
+            ]
+        );
+        assert_eq!(journal.sections().len(), 2);
+        assert_eq!(journal.section(physics).unwrap().children().len(), 2);
+
+        assert!(journal.remove_section(physics));
+        assert_eq!(journal.sections().len(), 3);
+        assert!(journal.section(physics).is_none());
+    }
+
+    #[test]
+    fn keeps_sections_consistent_with_pages() {
+        let mut journal = Journal::default();
+        for _ in 0..3 {
+            journal.push_back(Page::default());
+        }
+        let chapter = journal.add_section("Chapter", 1..=2).unwrap();
+        let section = journal.add_section("Section", 2..=2).unwrap();
+
+        journal.insert(0, Page::default());
+        assert_eq!(journal.section(chapter).unwrap().pages(), 2..=3);
+        journal.insert(3, Page::default());
+        assert_eq!(journal.section(chapter).unwrap().pages(), 2..=4);
+        assert_eq!(journal.section(section).unwrap().pages(), 4..=4);
+
+        let moved = journal.get(4).unwrap().id();
+        assert!(journal.move_page(4, 0));
+        assert_eq!(journal.section(section).unwrap().pages(), 0..=0);
+        assert_eq!(journal.index_of(moved), Some(0));
+        assert_eq!(journal.section(chapter).unwrap().pages(), 3..=4);
+
+        journal.remove(0);
+        assert!(journal.section(section).is_none());
+        journal.remove(2);
+        journal.remove(2);
+        assert!(journal.section(chapter).is_none());
+        assert!(journal.table_of_contents().is_empty());
+    }
+
+    #[test]
+    fn lists_and_searches_bookmarks() {
+        let mut journal = Journal::default();
+        journal.push_back(Page::default());
+        let first = journal.get(0).unwrap().id();
+        let second = journal.get(1).unwrap().id();
+        let red = Color::rgb(255, 0, 0);
+        let area = AABB::from_corners((0, 0).into(), (100, 100).into());
+
+        let intro = journal
+            .add_bookmark("Introduction", red, first, None)
+            .unwrap();
+        let figure = journal
+            .add_bookmark("Figure 1", red, second, Some(area))
+            .unwrap();
+        assert!(journal
+            .add_bookmark("Missing", red, Page::default().id(), None)
+            .is_none());
+
+        let labels = |bookmarks: Vec<&Bookmark>| -> Vec<String> {
+            bookmarks
+                .into_iter()
+                .map(|bookmark| bookmark.label().to_string())
+                .collect()
+        };
+        assert_eq!(
+            labels(journal.bookmarks().iter().collect()),
+            vec!["Introduction", "Figure 1"]
+        );
+        assert_eq!(
+            labels(journal.find_bookmarks("figure").collect()),
+            vec!["Figure 1"]
+        );
+        assert_eq!(journal.bookmarks_on_page(first).count(), 1);
+
+        journal
+            .bookmark_mut(figure)
+            .unwrap()
+            .set_color(Color::rgb(0, 0, 255));
+        assert_eq!(
+            journal.bookmark(figure).unwrap().color(),
+            Color::rgb(0, 0, 255)
+        );
+        let resolved = journal.resolve_bookmark(figure).unwrap();
+        assert_eq!(resolved.index, 1);
+        assert_eq!(resolved.viewport, Some(area));
+
+        assert_eq!(
+            journal.remove_bookmark(intro).unwrap().label(),
+            "Introduction"
+        );
+        assert!(journal.bookmark(intro).is_none());
+        assert!(journal.resolve_bookmark(intro).is_none());
+    }
+
+    #[test]
+    fn element_bookmarks_dangle_once_the_element_is_removed() {
+        let mut journal = Journal::default();
+        let page = journal.get(0).unwrap().id();
+        let element = journal
+            .get_mut(0)
+            .unwrap()
+            .insert(Line::from_iter([(0, 0), (10, 20)].iter()), 0);
+        let area = AABB::from_corners((0, 0).into(), (10, 20).into());
+        let bookmark = journal
+            .add_element_bookmark("Sketch", Color::rgb(0, 0, 0), page, element)
+            .unwrap();
+        assert!(journal
+            .add_element_bookmark("Missing", Color::rgb(0, 0, 0), page, ElementId::new(42))
+            .is_none());
+
+        let resolved = journal.resolve_bookmark(bookmark).unwrap();
+        assert_eq!(resolved.element.unwrap().id(), element);
+        assert_eq!(resolved.viewport, Some(area));
+
+        assert!(journal.dangling_bookmarks().is_empty());
+
+        journal.get_mut(0).unwrap().extract_by_id(element);
+        assert!(journal.resolve_bookmark(bookmark).is_none());
+        assert_eq!(journal.dangling_bookmarks(), vec![bookmark]);
+        assert_eq!(journal.bookmark(bookmark).unwrap().viewport(), Some(area));
+    }
+
+    #[test]
+    fn bookmarks_follow_moved_pages() {
+        let mut journal = Journal::default();
+        journal.push_back(Page::default());
+        journal.push_back(Page::default());
+        let last = journal.get(2).unwrap().id();
+        let bookmark = journal
+            .add_bookmark("Last", Color::rgb(0, 0, 0), last, None)
+            .unwrap();
+
+        assert!(journal.move_page(2, 0));
+        let resolved = journal.resolve_bookmark(bookmark).unwrap();
+        assert_eq!(resolved.index, 0);
+        assert_eq!(resolved.page.id(), last);
+
+        journal.remove(1);
+        assert_eq!(journal.resolve_bookmark(bookmark).unwrap().index, 0);
+        journal.remove(0);
+        assert!(journal.resolve_bookmark(bookmark).is_none());
+        assert_eq!(journal.dangling_bookmarks(), vec![bookmark]);
+    }
+
+    #[test]
+    fn bookmarks_of_removed_pages_dangle() {
+        let mut journal = Journal::default();
+        journal.push_back(Page::default());
+        journal.push_back(Page::default());
+        let ids: Vec<PageId> = journal.iter().map(|page| page.id()).collect();
+        let area = AABB::from_corners((0, 0).into(), (1, 1).into());
+        let middle = journal
+            .add_bookmark("Middle", Color::rgb(0, 0, 0), ids[1], Some(area))
+            .unwrap();
+        let last = journal
+            .add_bookmark("Last", Color::rgb(0, 0, 0), ids[2], None)
+            .unwrap();
+
+        journal.remove(1);
+        let bookmark = journal.bookmark(middle).unwrap();
+        assert_eq!(bookmark.page(), ids[1]);
+        assert_eq!(bookmark.viewport(), Some(area));
+        assert!(journal.resolve_bookmark(middle).is_none());
+        assert_eq!(journal.resolve_bookmark(last).unwrap().index, 1);
+        assert_eq!(journal.dangling_bookmarks(), vec![middle]);
+
+        let removed = journal.remove_dangling_bookmarks();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id(), middle);
+        assert!(journal.bookmark(middle).is_none());
+        assert!(journal.dangling_bookmarks().is_empty());
+        assert!(journal.remove_dangling_bookmarks().is_empty());
+    }
+
+    #[test]
+    fn tracks_modification_of_metadata_and_pages() {
+        let clock = Arc::new(ManualClock::default());
+        clock.set(1);
+        let mut journal = Journal::with_clock(clock.clone());
+        assert_eq!(journal.metadata().created(), ManualClock::at(1));
+        assert_eq!(journal.modified(), ManualClock::at(1));
+
+        clock.set(2);
+        journal.update_metadata(|metadata| metadata.set_title("Notes"));
+        assert_eq!(journal.metadata().title(), "Notes");
+        assert_eq!(journal.modified(), ManualClock::at(2));
+
+        clock.set(3);
+        journal.get_mut(0).unwrap().insert(Line::default(), 0);
+        assert_eq!(journal.metadata().modified(), ManualClock::at(2));
+        assert_eq!(journal.modified(), ManualClock::at(3));
+
+        clock.set(4);
+        journal.push_back(Page::with_clock(clock.clone()));
+        assert_eq!(journal.metadata().modified(), ManualClock::at(4));
+    }
+
+    #[test]
+    fn added_pages_use_the_journal_clock() {
+        let clock = Arc::new(ManualClock::default());
+        clock.set(5);
+        let mut journal = Journal::with_clock(clock.clone());
+        journal.push_back(Page::default());
+        journal.insert(0, Page::default());
+
+        for page in journal.iter_mut() {
+            let id = page.insert(Line::default(), 0);
+            assert_eq!(page.get(id).unwrap().created(), Some(ManualClock::at(5)));
+        }
+    }
+
+    #[test]
+    fn restores_stored_metadata() {
+        let clock = Arc::new(ManualClock::default());
+        clock.set(1);
+        let mut stored = Journal::with_clock(clock.clone());
+        stored.update_metadata(|metadata| metadata.set_title("Notes"));
+        let json = serde_json::to_string(stored.metadata()).unwrap();
+
+        clock.set(2);
+        let mut journal = Journal::with_clock(clock.clone());
+        journal.set_metadata(serde_json::from_str(&json).unwrap());
+        assert_eq!(journal.metadata(), stored.metadata());
+        assert_eq!(journal.metadata().modified(), ManualClock::at(1));
+    }
+
+    #[test]
+    fn exports_metadata_with_journal_modification_time() {
+        let clock = Arc::new(ManualClock::default());
+        let mut journal = Journal::with_clock(clock.clone());
+        journal.update_metadata(|metadata| metadata.set_authors(["Alice"]));
+        clock.set(86400);
+        journal.get_mut(0).unwrap().set_title("First");
+
+        assert!(journal
+            .svg_metadata()
+            .contains("<dcterms:modified>1970-01-02T00:00:00Z</dcterms:modified>"));
+        assert!(journal
+            .pdf_info()
+            .contains(&("ModDate".to_string(), "D:19700102000000Z".to_string())));
+    }
 }
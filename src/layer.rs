@@ -0,0 +1,98 @@
+/// Identifies a layer within its page.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Default, Clone, Copy, Hash)]
+pub struct LayerId(u64);
+
+impl LayerId {
+    pub(crate) fn new(id: u64) -> Self {
+        LayerId(id)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A named group of elements that is painted, shown and locked as a whole.
+///
+/// Layers are owned by a `Page`, which also keeps their order.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Layer {
+    id: LayerId,
+    name: String,
+    visible: bool,
+    locked: bool,
+    opacity: f32,
+}
+
+impl Layer {
+    pub(crate) fn new(id: LayerId, name: impl Into<String>) -> Self {
+        Layer {
+            id,
+            name: name.into(),
+            visible: true,
+            locked: false,
+            opacity: 1.0,
+        }
+    }
+
+    pub fn id(&self) -> LayerId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    /// A value between 0 (transparent) and 1 (opaque).
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Sets the opacity, clamped to the range from 0 to 1.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_layer_is_visible_unlocked_and_opaque() {
+        let layer = Layer::new(LayerId::new(1), "Sketch");
+        assert_eq!(layer.name(), "Sketch");
+        assert!(layer.is_visible());
+        assert!(!layer.is_locked());
+        assert_eq!(layer.opacity(), 1.0);
+    }
+
+    #[test]
+    fn opacity_is_clamped() {
+        let mut layer = Layer::new(LayerId::default(), "Background");
+        layer.set_opacity(1.5);
+        assert_eq!(layer.opacity(), 1.0);
+        layer.set_opacity(-0.5);
+        assert_eq!(layer.opacity(), 0.0);
+    }
+}
@@ -1,15 +1,56 @@
+mod affine;
+mod author;
+mod background;
+mod bookmark;
+mod clock;
+mod color;
 mod elements;
+mod geometry;
+mod history;
 mod journal;
+mod layer;
+mod metadata;
 mod page;
 mod point;
 mod iterator;
+mod replay;
+mod section;
+mod stroke;
+mod unit;
+mod vector;
 
-pub use crate::journal::Journal;
-pub use crate::page::Page;
-pub use crate::point::Point;
+pub use crate::affine::Affine;
+pub use crate::author::AuthorId;
+pub use crate::background::{Background, BackgroundMark};
+pub use crate::bookmark::{Bookmark, BookmarkId, ResolvedBookmark};
+pub use crate::clock::{Clock, SystemClock};
+pub use crate::color::Color;
+pub use crate::geometry::{Orientation, PageGeometry, Paper};
+pub use crate::history::{Snapshot, Tombstone};
+pub use crate::journal::{Journal, ResolvedLink};
+pub use crate::layer::{Layer, LayerId};
+pub use crate::metadata::JournalMetadata;
+pub use crate::page::{Page, PageId};
+pub use crate::point::{FloatPoint, Point};
+pub use crate::replay::{DrawEvent, Replay, Visible};
+pub use crate::section::{Section, SectionId, TocEntry};
+pub use crate::stroke::recognize::{Recognizer, Shape, Suggestion};
+pub use crate::stroke::simplify::Simplification;
+pub use crate::stroke::smooth::Smoothing;
+pub use crate::unit::{Dpi, Length, Unit, MODEL_UNITS_PER_MILLIMETRE};
+pub use crate::vector::Vector;
 
-pub use elements::element::Element;
+pub use elements::element::{Element, ElementId, Transform};
 pub use elements::timed_element::TimedElement;
 
+pub use elements::arrow::Arrow;
+pub use elements::connector::{Anchor, AnchorPoint, Connector};
+pub use elements::ellipse::Ellipse;
+pub use elements::formula::{Formula, FormulaRenderer, FormulaRendering};
+pub use elements::group::Group;
 pub use elements::line::Line;
+pub use elements::link::{Link, LinkTarget};
+pub use elements::polygon::Polygon;
 pub use elements::rectangle::Rectangle;
+pub use elements::sticky_note::{NoteContent, StickyNote};
+pub use elements::text::Text;
@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Keys of the standard entries of a PDF document information dictionary.
+const PDF_INFO_KEYS: [&str; 9] = [
+    "Title",
+    "Author",
+    "Subject",
+    "Keywords",
+    "Creator",
+    "Producer",
+    "CreationDate",
+    "ModDate",
+    "Trapped",
+];
+
+/// Descriptive information about a journal.
+///
+/// The metadata is serializable so it can be stored next to the pages and restored with
+/// `Journal::set_metadata`.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct JournalMetadata {
+    title: String,
+    authors: Vec<String>,
+    description: String,
+    language: Option<String>,
+    created: SystemTime,
+    modified: SystemTime,
+    properties: BTreeMap<String, String>,
+}
+
+impl JournalMetadata {
+    pub(crate) fn new(created: SystemTime) -> Self {
+        JournalMetadata {
+            title: String::new(),
+            authors: Vec::new(),
+            description: String::new(),
+            language: None,
+            created,
+            modified: created,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    pub fn authors(&self) -> &[String] {
+        &self.authors
+    }
+
+    pub fn set_authors<T: IntoIterator<Item = S>, S: Into<String>>(&mut self, authors: T) {
+        self.authors = authors.into_iter().map(Into::into).collect();
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        self.description = description.into();
+    }
+
+    /// A language tag such as `en-GB`.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// The time the metadata or the order of pages last changed, see `Journal::modified`.
+    pub fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    pub(crate) fn set_modified(&mut self, modified: SystemTime) {
+        self.modified = modified;
+    }
+
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    /// Iterates over the custom properties ordered by key.
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Sets a custom property and returns its previous value.
+    pub fn set_property(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.properties.insert(key.into(), value.into())
+    }
+
+    pub fn remove_property(&mut self, key: &str) -> Option<String> {
+        self.properties.remove(key)
+    }
+
+    /// Renders an SVG `<metadata>` element using Dublin Core terms.
+    ///
+    /// `modified` is passed separately because a journal is also modified by its pages. Custom
+    /// properties have no Dublin Core equivalent and are not included.
+    pub fn to_svg(&self, modified: SystemTime) -> String {
+        let mut fields = vec![format!("<dc:title>{}</dc:title>", escape(&self.title))];
+        fields.extend(
+            self.authors
+                .iter()
+                .map(|author| format!("<dc:creator>{}</dc:creator>", escape(author))),
+        );
+        if !self.description.is_empty() {
+            fields.push(format!(
+                "<dc:description>{}</dc:description>",
+                escape(&self.description)
+            ));
+        }
+        if let Some(language) = &self.language {
+            fields.push(format!("<dc:language>{}</dc:language>", escape(language)));
+        }
+        fields.push(format!("<dc:date>{}</dc:date>", iso_8601(self.created)));
+        fields.push(format!(
+            "<dcterms:modified>{}</dcterms:modified>",
+            iso_8601(modified)
+        ));
+
+        let mut svg = String::from(
+            "<metadata>\n\
+             <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+             xmlns:dcterms=\"http://purl.org/dc/terms/\">\n\
+             <rdf:Description>\n",
+        );
+        for field in fields {
+            svg.push_str(&field);
+            svg.push('\n');
+        }
+        svg.push_str("</rdf:Description>\n</rdf:RDF>\n</metadata>");
+        svg
+    }
+
+    /// Returns the entries of a PDF document information dictionary.
+    ///
+    /// Keys are PDF names without the leading slash, with irregular characters escaped as `#xx`.
+    /// Values are unescaped text; dates use the PDF date format. Custom properties follow the
+    /// standard entries, properties whose key is a standard key such as `Title` or `Producer`
+    /// are skipped. The caller must escape the values when writing them as PDF strings, e.g.
+    /// backslashes and unbalanced parentheses of literal strings.
+    pub fn to_pdf_info(&self, modified: SystemTime) -> Vec<(String, String)> {
+        let mut info = vec![("Title".to_string(), self.title.clone())];
+        if !self.authors.is_empty() {
+            info.push(("Author".to_string(), self.authors.join("; ")));
+        }
+        if !self.description.is_empty() {
+            info.push(("Subject".to_string(), self.description.clone()));
+        }
+        info.push(("CreationDate".to_string(), pdf_date(self.created)));
+        info.push(("ModDate".to_string(), pdf_date(modified)));
+        info.extend(
+            self.properties
+                .iter()
+                .filter(|(key, _)| !PDF_INFO_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (pdf_name(key), value.clone())),
+        );
+        info
+    }
+}
+
+/// Escapes whitespace, delimiters, `#` and non-ASCII bytes of a PDF name as `#xx`.
+fn pdf_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'!'..=b'~' if !b"#%()/<>[]{}".contains(&byte) => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("#{byte:02X}")),
+        }
+    }
+    escaped
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Splits a time into UTC year, month, day, hour, minute and second.
+fn civil(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs() as i64),
+    };
+    let (days, seconds) = (seconds.div_euclid(86400), seconds.rem_euclid(86400) as u32);
+    // Converts days since the epoch to a proleptic Gregorian date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
+}
+
+fn iso_8601(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil(time);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+fn pdf_date(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil(time);
+    format!("D:{year:04}{month:02}{day:02}{hour:02}{minute:02}{second:02}Z")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 2024-02-29T13:45:30Z
+    fn leap_day() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_709_214_330)
+    }
+
+    fn metadata() -> JournalMetadata {
+        let mut metadata = JournalMetadata::new(leap_day());
+        metadata.set_title("Physics & Maths");
+        metadata.set_authors(["Alice", "Bob"]);
+        metadata.set_description("Lecture notes");
+        metadata.set_language(Some("en-GB".to_string()));
+        metadata.set_property("Course", "PHY101");
+        metadata
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(iso_8601(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(iso_8601(leap_day()), "2024-02-29T13:45:30Z");
+        assert_eq!(pdf_date(leap_day()), "D:20240229134530Z");
+    }
+
+    #[test]
+    fn stores_custom_properties() {
+        let mut metadata = metadata();
+        assert_eq!(metadata.property("Course"), Some("PHY101"));
+        assert_eq!(
+            metadata.set_property("Course", "PHY102"),
+            Some("PHY101".to_string())
+        );
+        assert_eq!(
+            metadata.properties().collect::<Vec<_>>(),
+            vec![("Course", "PHY102")]
+        );
+        assert_eq!(
+            metadata.remove_property("Course"),
+            Some("PHY102".to_string())
+        );
+        assert!(metadata.property("Course").is_none());
+    }
+
+    #[test]
+    fn exports_svg_metadata() {
+        let svg = metadata().to_svg(leap_day() + Duration::from_secs(60));
+        assert!(svg.starts_with("<metadata>"));
+        assert!(svg.contains("<dc:title>Physics &amp; Maths</dc:title>"));
+        assert!(svg.contains("<dc:creator>Alice</dc:creator>\n<dc:creator>Bob</dc:creator>"));
+        assert!(svg.contains("<dc:description>Lecture notes</dc:description>"));
+        assert!(svg.contains("<dc:language>en-GB</dc:language>"));
+        assert!(svg.contains("<dc:date>2024-02-29T13:45:30Z</dc:date>"));
+        assert!(svg.contains("<dcterms:modified>2024-02-29T13:46:30Z</dcterms:modified>"));
+        assert!(svg.ends_with("</metadata>"));
+    }
+
+    #[test]
+    fn exports_pdf_info() {
+        let info = metadata().to_pdf_info(leap_day());
+        let entry = |key: &str| {
+            info.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(entry("Title"), Some("Physics & Maths"));
+        assert_eq!(entry("Author"), Some("Alice; Bob"));
+        assert_eq!(entry("Subject"), Some("Lecture notes"));
+        assert_eq!(entry("CreationDate"), Some("D:20240229134530Z"));
+        assert_eq!(entry("ModDate"), Some("D:20240229134530Z"));
+        assert_eq!(entry("Course"), Some("PHY101"));
+    }
+
+    #[test]
+    fn pdf_info_skips_standard_keys_and_escapes_names() {
+        let mut metadata = metadata();
+        metadata.set_property("Title", "Overwritten");
+        metadata.set_property("Producer", "Someone else");
+        metadata.set_property("Lecture room (A/1)", "Hall #2");
+        metadata.set_property("Übung", "3");
+        let info = metadata.to_pdf_info(leap_day());
+
+        let titles: Vec<&str> = info
+            .iter()
+            .filter(|(name, _)| name == "Title")
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Physics & Maths"]);
+        assert!(info.iter().all(|(name, _)| name != "Producer"));
+        assert!(info.contains(&(
+            "Lecture#20room#20#28A#2F1#29".to_string(),
+            "Hall #2".to_string()
+        )));
+        assert!(info.contains(&("#C3#9Cbung".to_string(), "3".to_string())));
+    }
+
+    #[test]
+    fn escapes_pdf_names() {
+        assert_eq!(pdf_name("Course"), "Course");
+        assert_eq!(pdf_name("a#b%c"), "a#23b#25c");
+        assert_eq!(pdf_name("tab\t[x]"), "tab#09#5Bx#5D");
+    }
+
+    #[test]
+    fn survives_serialization() {
+        let mut metadata = metadata();
+        metadata.set_modified(leap_day() + Duration::from_nanos(1));
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(
+            serde_json::from_str::<JournalMetadata>(&json).unwrap(),
+            metadata
+        );
+    }
+}
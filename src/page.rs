@@ -1,39 +1,713 @@
-use crate::{Element, Point};
+use crate::{
+    Affine, AuthorId, Background, BackgroundMark, Clock, Connector, Element, ElementId, FloatPoint,
+    Formula, FormulaRenderer, Group, Layer, LayerId, Line, PageGeometry, Point, Polygon, Shape,
+    Simplification, SystemClock, Vector,
+};
 
 use crate::elements::timed_element::TimedElement;
+use crate::history::{Snapshot, Tombstone};
 use crate::iterator::SelectionIter;
+use crate::stroke::simplify::distance_to_segment;
 use rstar::{Envelope, RTree, RTreeObject, SelectionFunction, AABB};
+use std::collections::{BTreeSet, HashMap};
+use std::slice::Iter;
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
 
-#[derive(Default)]
+/// A random identifier that stays the same across sessions, so links can be stored.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct PageId(u128);
+
+impl PageId {
+    /// Restores a stored id, see `Page::with_id`.
+    pub fn new(id: u128) -> Self {
+        PageId(id)
+    }
+
+    fn random() -> Self {
+        PageId(Uuid::new_v4().as_u128())
+    }
+
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+}
+
+#[derive(Debug)]
 pub struct Page {
+    id: PageId,
     elements: RTree<TimedElement>,
+    envelopes: HashMap<ElementId, AABB<Point>>,
+    /// Maps an element id to the connectors that reference it.
+    connectors: HashMap<ElementId, BTreeSet<ElementId>>,
+    /// Maps the id of a grouped element to the id of its group.
+    parents: HashMap<ElementId, ElementId>,
+    /// The elements of each layer in paint order, see `TimedElement::cmp`.
+    paint_order: HashMap<LayerId, BTreeSet<(i32, u128, ElementId)>>,
     t_index: u128,
+    tombstones: Vec<Tombstone>,
+    simplify_on_insert: Option<Simplification>,
+    removed_points: usize,
+    clock: Arc<dyn Clock>,
+    author: Option<AuthorId>,
+    /// Ordered from bottom to top, never empty.
+    layers: Vec<Layer>,
+    next_layer_id: u64,
+    active_layer: LayerId,
+    geometry: PageGeometry,
+    background: Background,
+    title: String,
+    tags: BTreeSet<String>,
+    created: SystemTime,
+    modified: SystemTime,
 }
 
 impl Page {
-    pub fn insert<T: Element + 'static>(&mut self, element: T, z_index: i32) {
+    /// Creates an empty page whose timestamps are taken from the given clock.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Page::new(PageId::random(), clock)
+    }
+
+    /// Creates an empty page with a previously stored id.
+    ///
+    /// The caller is responsible for not adding two pages with the same id to a journal.
+    pub fn with_id(id: PageId) -> Self {
+        Page::new(id, Arc::new(SystemClock))
+    }
+
+    fn new(id: PageId, clock: Arc<dyn Clock>) -> Self {
+        let now = clock.now();
+        Page {
+            id,
+            elements: RTree::default(),
+            envelopes: HashMap::default(),
+            connectors: HashMap::default(),
+            parents: HashMap::default(),
+            paint_order: HashMap::default(),
+            t_index: 0,
+            tombstones: Vec::new(),
+            simplify_on_insert: None,
+            removed_points: 0,
+            clock,
+            author: None,
+            layers: vec![Layer::new(LayerId::default(), "Default")],
+            next_layer_id: 1,
+            active_layer: LayerId::default(),
+            geometry: PageGeometry::default(),
+            background: Background::default(),
+            title: String::new(),
+            tags: BTreeSet::new(),
+            created: now,
+            modified: now,
+        }
+    }
+
+    pub fn id(&self) -> PageId {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+        self.touch();
+    }
+
+    /// Iterates over the tags in alphabetical order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Returns `false` if the page already has the tag.
+    pub fn add_tag(&mut self, tag: impl Into<String>) -> bool {
+        let added = self.tags.insert(tag.into());
+        if added {
+            self.touch();
+        }
+        added
+    }
+
+    /// Returns `false` if the page does not have the tag.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let removed = self.tags.remove(tag);
+        if removed {
+            self.touch();
+        }
+        removed
+    }
+
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// The time of the last change to the page's elements or metadata.
+    pub fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    /// Inserts an element into the active layer.
+    pub fn insert<T: Element + 'static>(&mut self, element: T, z_index: i32) -> ElementId {
+        self.insert_on_layer(element, z_index, self.active_layer)
+    }
+
+    fn insert_on_layer<T: Element + 'static>(
+        &mut self,
+        element: T,
+        z_index: i32,
+        layer: LayerId,
+    ) -> ElementId {
         self.t_index += 1;
-        self.elements
-            .insert(TimedElement::new(element, z_index, self.t_index));
+        let mut element = TimedElement::new(element, z_index, self.t_index);
+        element.set_created(self.clock.now(), self.author.clone());
+        element.set_layer(layer);
+        if let (Some(simplification), Some(line)) =
+            (self.simplify_on_insert, element.downcast_mut::<Line>())
+        {
+            self.removed_points += line.simplify(simplification);
+        }
+        self.route(&mut element);
+        let id = element.id();
+        self.insert_timed(element);
+        id
+    }
+
+    /// Sets the clock used to timestamp elements that are inserted or modified afterwards.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn author(&self) -> Option<&AuthorId> {
+        self.author.as_ref()
+    }
+
+    /// Sets the author recorded on every element inserted afterwards.
+    pub fn set_author(&mut self, author: Option<AuthorId>) {
+        self.author = author;
+    }
+
+    pub fn geometry(&self) -> PageGeometry {
+        self.geometry
+    }
+
+    pub fn set_geometry(&mut self, geometry: PageGeometry) {
+        self.geometry = geometry;
+        self.touch();
+    }
+
+    /// Yields the elements that are not completely inside the page bounds.
+    ///
+    /// Infinite pages never overflow.
+    pub fn overflowing(&self) -> SelectionIter<'_> {
+        let bounds = self.geometry.bounds();
+        SelectionIter::new(Box::new(self.elements.iter().filter(move |element| {
+            bounds.is_some_and(|bounds| !bounds.contains_envelope(&element.envelope()))
+        })))
+    }
+
+    pub fn background(&self) -> Background {
+        self.background
+    }
+
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+        self.touch();
+    }
+
+    /// Generates the background marks visible in the viewport, clipped to the page bounds.
+    pub fn background_marks(&self, viewport: AABB<Point>) -> Vec<BackgroundMark> {
+        self.background.marks(viewport, self.geometry.bounds())
+    }
+
+    /// Iterates over the layers from bottom to top.
+    pub fn layers(&self) -> Iter<'_, Layer> {
+        self.layers.iter()
+    }
+
+    pub fn layer(&self, id: LayerId) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.id() == id)
+    }
+
+    pub fn layer_mut(&mut self, id: LayerId) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.id() == id)
     }
 
-    pub fn iter(&self) -> SelectionIter {
+    /// Adds a new layer on top of all others.
+    pub fn add_layer(&mut self, name: impl Into<String>) -> LayerId {
+        let id = LayerId::new(self.next_layer_id);
+        self.next_layer_id += 1;
+        self.layers.push(Layer::new(id, name));
+        id
+    }
+
+    /// Removes a layer and extracts all of its elements.
+    ///
+    /// Returns `false` if there is no such layer or it is the last one. If the removed layer was
+    /// active, the top layer becomes active.
+    pub fn remove_layer(&mut self, id: LayerId) -> bool {
+        let Some(position) = self.layer_position(id) else {
+            return false;
+        };
+        if self.layers.len() == 1 {
+            return false;
+        }
+        let ids: Vec<ElementId> = self
+            .paint_order
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|(_, _, id)| *id)
+            .collect();
+        for id in ids {
+            self.extract_by_id(id);
+        }
+        self.layers.remove(position);
+        if self.active_layer == id {
+            self.active_layer = self.layers[self.layers.len() - 1].id();
+        }
+        true
+    }
+
+    /// Moves a layer to the given position, where 0 is the bottom.
+    ///
+    /// The elements keep their `z_index`. Returns `false` if there is no such layer.
+    pub fn move_layer(&mut self, id: LayerId, to: usize) -> bool {
+        let Some(position) = self.layer_position(id) else {
+            return false;
+        };
+        let layer = self.layers.remove(position);
+        self.layers.insert(to.min(self.layers.len()), layer);
+        true
+    }
+
+    /// The layer new elements are inserted into.
+    pub fn active_layer(&self) -> LayerId {
+        self.active_layer
+    }
+
+    /// Returns `false` if there is no such layer.
+    pub fn set_active_layer(&mut self, id: LayerId) -> bool {
+        if self.layer(id).is_none() {
+            return false;
+        }
+        self.active_layer = id;
+        true
+    }
+
+    /// Moves the elements with the given ids to a layer.
+    ///
+    /// Returns the number of elements that were moved, or 0 if there is no such layer.
+    pub fn move_to_layer(&mut self, ids: &[ElementId], layer: LayerId) -> usize {
+        if self.layer(layer).is_none() {
+            return 0;
+        }
+        let mut moved = 0;
+        for id in ids {
+            if let Some(mut element) = self.take(*id) {
+                element.set_layer(layer);
+                element.set_modified(self.clock.now());
+                self.insert_timed(element);
+                moved += 1;
+            }
+        }
+        moved
+    }
+
+    pub fn simplify_on_insert(&self) -> Option<Simplification> {
+        self.simplify_on_insert
+    }
+
+    /// Sets the simplification that is applied to every `Line` inserted afterwards.
+    pub fn set_simplify_on_insert(&mut self, simplification: Option<Simplification>) {
+        self.simplify_on_insert = simplification;
+    }
+
+    /// The total number of points removed by simplification on insert.
+    pub fn removed_points(&self) -> usize {
+        self.removed_points
+    }
+
+    pub fn iter(&self) -> SelectionIter<'_> {
         SelectionIter::new(Box::new(self.elements.iter()))
     }
 
+    /// Iterates over all elements in the order they have to be painted, back to front.
+    ///
+    /// Elements are painted layer by layer and ordered within their layer. The order is kept in
+    /// an index, so iterating does not sort.
+    pub fn iter_paint_order(&self) -> SelectionIter<'_> {
+        SelectionIter::new(Box::new(
+            self.layers
+                .iter()
+                .filter_map(|layer| self.paint_order.get(&layer.id()))
+                .flatten()
+                .filter_map(|(_, _, id)| self.get(*id)),
+        ))
+    }
+
+    /// Iterates in paint order over the elements that are not hidden and on visible layers.
+    pub fn iter_visible(&self) -> SelectionIter<'_> {
+        SelectionIter::new(Box::new(self.iter_paint_order().filter(|element| {
+            !element.is_hidden() && self.layer_is(element, Layer::is_visible)
+        })))
+    }
+
+    pub fn get(&self, id: ElementId) -> Option<&TimedElement> {
+        let envelope = self.envelopes.get(&id)?;
+        self.elements
+            .locate_with_selection_function(SelectByIdFunction::new(*envelope, id))
+            .next()
+    }
+
+    /// Removes an element from the page.
+    ///
+    /// Like every write, this advances the `t_index` of the page. A tombstone keeps a copy of the
+    /// element so that time-travel queries still see it before its removal.
     pub fn extract(
         &mut self,
         selection_function: SelectByAddressFunction<TimedElement>,
     ) -> Option<TimedElement> {
-        self.elements
+        let element = self
+            .elements
             .drain_with_selection_function(selection_function)
-            .next()
+            .next()?;
+        self.forget(&element);
+        self.bury(&element);
+        Some(element)
+    }
+
+    /// Removes the element with the given id from the page, see `Page::extract`.
+    pub fn extract_by_id(&mut self, id: ElementId) -> Option<TimedElement> {
+        let element = self.take(id)?;
+        self.bury(&element);
+        Some(element)
+    }
+
+    /// The `t_index` of the most recent write to this page.
+    pub fn t_index(&self) -> u128 {
+        self.t_index
+    }
+
+    pub fn tombstones(&self) -> Iter<'_, Tombstone> {
+        self.tombstones.iter()
+    }
+
+    /// Iterates in write order over all elements written up to and including `t_index`, whether
+    /// or not they have been extracted, replaced or grouped since.
+    pub fn iter_until(&self, t_index: u128) -> SelectionIter<'_> {
+        let mut elements: Vec<&TimedElement> = self
+            .elements
+            .iter()
+            .chain(self.tombstones.iter().map(|tombstone| tombstone.element()))
+            .filter(|element| element.revision() <= t_index)
+            .collect();
+        elements.sort_by_key(|element| (element.revision(), element.t_index()));
+        SelectionIter::new(Box::new(elements.into_iter()))
+    }
+
+    /// Returns the elements that were present on the page at `t_index`.
+    ///
+    /// Modified, replaced, grouped and ungrouped elements appear as they were at `t_index`.
+    pub fn snapshot_at(&self, t_index: u128) -> Snapshot<'_> {
+        let elements = self
+            .elements
+            .iter()
+            .filter(|element| element.revision() <= t_index)
+            .chain(
+                self.tombstones
+                    .iter()
+                    .filter(|tombstone| tombstone.was_present_at(t_index))
+                    .map(|tombstone| tombstone.element()),
+            )
+            .collect();
+        Snapshot::new(t_index, elements)
+    }
+
+    /// Modifies the element with the given id in place.
+    ///
+    /// The element is extracted, modified and reinserted, keeping its id, `z_index` and
+    /// `t_index`. Like every write, this advances the `t_index` of the page and keeps the previous
+    /// state as a tombstone. Connectors attached to the element are re-routed afterwards. Returns
+    /// `false` if there is no such element or it is not of type `T`.
+    pub fn update<T, F>(&mut self, id: ElementId, modify: F) -> bool
+    where
+        T: Element + 'static,
+        F: FnOnce(&mut T),
+    {
+        let Some(mut element) = self.take(id) else {
+            return false;
+        };
+        let previous = element.clone();
+        let updated = match element.downcast_mut::<T>() {
+            Some(inner) => {
+                modify(inner);
+                self.t_index += 1;
+                self.revise(&mut element, previous);
+                true
+            }
+            None => false,
+        };
+        self.route(&mut element);
+        self.insert_timed(element);
+        if updated {
+            self.reroute_connectors(id);
+        }
+        updated
+    }
+
+    /// Replaces an element while keeping its id, `z_index` and `t_index`.
+    ///
+    /// The replaced element is kept as a tombstone for time-travel queries, see
+    /// `TimedElement::revision`. Connectors attached to the element are re-routed. Returns `false`
+    /// if there is no such element.
+    pub fn replace<T: Element + 'static>(&mut self, id: ElementId, element: T) -> bool {
+        self.replace_boxed(id, Box::new(element))
+    }
+
+    /// Replaces a stroke with a recognized shape, see `Page::replace`.
+    pub fn replace_with_shape(&mut self, id: ElementId, shape: Shape) -> bool {
+        self.replace_boxed(id, shape.into_element())
+    }
+
+    /// Moves the elements with the given ids into a new group.
+    ///
+    /// The group is placed at the highest `z_index` of its children. Connectors attached to a
+    /// child stay attached and follow it when the group is modified. Unknown ids are ignored;
+    /// returns `None` if none of the ids belong to an element of this page.
+    pub fn group(&mut self, ids: &[ElementId]) -> Option<ElementId> {
+        let children: Vec<TimedElement> = ids.iter().filter_map(|id| self.take(*id)).collect();
+        let z_index = children.iter().map(|child| child.z_index()).max()?;
+        let layer = children
+            .iter()
+            .map(|child| child.layer())
+            .max_by_key(|layer| self.layer_position(*layer))?;
+        let history = children.clone();
+        let group = self.insert_on_layer(Group::new(children), z_index, layer);
+        for child in history {
+            self.parents.insert(child.id(), group);
+            self.entomb(child);
+        }
+        Some(group)
+    }
+
+    /// Dissolves a group and moves its children back onto the page.
+    ///
+    /// The children keep their ids, `z_index` and `t_index`; the group is kept as a tombstone.
+    /// Connectors among the children are re-routed. Returns the ids of the children, or `None` if
+    /// the id does not belong to a group.
+    pub fn ungroup(&mut self, id: ElementId) -> Option<Vec<ElementId>> {
+        let mut element = self.take(id)?;
+        if element.downcast_ref::<Group>().is_none() {
+            self.insert_timed(element);
+            return None;
+        }
+        self.t_index += 1;
+        self.entomb(element.clone());
+        let group = element.downcast_mut::<Group>()?;
+        let children = std::mem::take(group).into_children();
+        let ids = children.iter().map(|child| child.id()).collect();
+        // Connectors go last so that they are routed to their endpoints among the children.
+        let (connectors, others): (Vec<_>, Vec<_>) = children
+            .into_iter()
+            .partition(|child| child.downcast_ref::<Connector>().is_some());
+        for mut child in others.into_iter().chain(connectors) {
+            self.parents.remove(&child.id());
+            child.set_layer(element.layer());
+            child.set_revision(self.t_index);
+            self.route(&mut child);
+            self.insert_timed(child);
+        }
+        Some(ids)
+    }
+
+    /// Iterates over the connectors with an endpoint that is no longer part of the page.
+    ///
+    /// A connector keeps the last position of an endpoint whose element was extracted, erased or
+    /// removed with its layer.
+    pub fn dangling_connectors(&self) -> SelectionIter<'_> {
+        let connectors: BTreeSet<ElementId> = self.connectors.values().flatten().copied().collect();
+        let dangling: Vec<&TimedElement> = connectors
+            .into_iter()
+            .filter_map(|id| self.get(id))
+            .filter(|element| {
+                element
+                    .downcast_ref::<Connector>()
+                    .is_some_and(|connector| {
+                        self.find(connector.source().element()).is_none()
+                            || self.find(connector.target().element()).is_none()
+                    })
+            })
+            .collect();
+        SelectionIter::new(Box::new(dangling.into_iter()))
+    }
+
+    /// Applies an affine transformation to the elements with the given ids and reindexes them.
+    ///
+    /// All transformed elements share one new `t_index`, their previous states are kept as
+    /// tombstones. Connectors attached to transformed elements are re-routed. Returns the number
+    /// of elements that were transformed; unknown ids and elements that do not support the
+    /// transformation, see `Transform::supports`, are skipped and left unchanged.
+    pub fn transform_selection(&mut self, ids: &[ElementId], affine: &Affine) -> usize {
+        let mut transformed = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some(mut element) = self.take(*id) else {
+                continue;
+            };
+            let previous = element.clone();
+            if element.transform(affine) {
+                if transformed.is_empty() {
+                    self.t_index += 1;
+                }
+                self.revise(&mut element, previous);
+                transformed.push(*id);
+            }
+            self.route(&mut element);
+            self.insert_timed(element);
+        }
+        for id in &transformed {
+            self.reroute_connectors(*id);
+        }
+        transformed.len()
+    }
+
+    /// Renders all formulas whose source changed since they were last rendered.
+    ///
+    /// Returns the number of formulas that were rendered.
+    pub fn render_formulas(&mut self, renderer: &dyn FormulaRenderer) -> usize {
+        let stale: Vec<ElementId> = self
+            .elements
+            .iter()
+            .filter(|element| {
+                element
+                    .downcast_ref::<Formula>()
+                    .is_some_and(|formula| formula.needs_rendering())
+            })
+            .map(|element| element.id())
+            .collect();
+        for id in &stale {
+            self.update(*id, |formula: &mut Formula| {
+                formula.render(renderer);
+            });
+        }
+        stale.len()
+    }
+
+    /// Returns all elements created in the half-open interval `[from, to)`.
+    pub fn added_between(&self, from: SystemTime, to: SystemTime) -> SelectionIter<'_> {
+        SelectionIter::new(Box::new(self.elements.iter().filter(move |element| {
+            element
+                .created()
+                .is_some_and(|created| from <= created && created < to)
+        })))
+    }
+
+    pub fn by_author(&self, author: &AuthorId) -> SelectionIter<'_> {
+        let author = author.clone();
+        SelectionIter::new(Box::new(
+            self.elements
+                .iter()
+                .filter(move |element| element.author() == Some(&author)),
+        ))
+    }
+
+    /// Locks or unlocks an element. Returns `false` if there is no such element.
+    pub fn set_locked(&mut self, id: ElementId, locked: bool) -> bool {
+        self.modify_flags(id, |element| element.set_locked(locked))
+    }
+
+    /// Hides or shows an element. Returns `false` if there is no such element.
+    pub fn set_hidden(&mut self, id: ElementId, hidden: bool) -> bool {
+        self.modify_flags(id, |element| element.set_hidden(hidden))
+    }
+
+    /// Yields the elements intersecting the envelope.
+    ///
+    /// Locked and hidden elements as well as elements of locked or hidden layers are skipped, see
+    /// `Page::locate_all_in_envelope`.
+    pub fn locate_in_envelope(&self, envelope: AABB<Point>) -> SelectionIter<'_> {
+        SelectionIter::new(Box::new(
+            self.elements
+                .locate_in_envelope(&envelope)
+                .filter(|element| self.is_selectable(element)),
+        ))
+    }
+
+    /// Like `locate_in_envelope`, but with floating-point coordinates.
+    ///
+    /// The envelope is widened to the enclosing integer coordinates.
+    pub fn locate_in_float_envelope(&self, envelope: AABB<FloatPoint>) -> SelectionIter<'_> {
+        self.locate_in_envelope(AABB::from_corners(
+            envelope.lower().floor(),
+            envelope.upper().ceil(),
+        ))
     }
 
-    pub fn locate_in_envelope(&self, envelope: AABB<Point>) -> SelectionIter {
+    /// Like `locate_in_envelope`, but includes locked and hidden elements.
+    pub fn locate_all_in_envelope(&self, envelope: AABB<Point>) -> SelectionIter<'_> {
         SelectionIter::new(Box::new(self.elements.locate_in_envelope(&envelope)))
     }
 
+    /// Returns the topmost element within `tolerance` of the point.
+    ///
+    /// Lines are hit along their stroke, other elements anywhere in their envelope. Locked and
+    /// hidden elements are skipped, see `Page::hit_test_all`.
+    pub fn hit_test(&self, point: Point, tolerance: i128) -> Option<&TimedElement> {
+        self.hits(point, tolerance)
+            .filter(|element| self.is_selectable(element))
+            .max_by(|lhs, rhs| self.paint_order(lhs, rhs))
+    }
+
+    /// Like `hit_test`, but includes locked and hidden elements.
+    pub fn hit_test_all(&self, point: Point, tolerance: i128) -> Option<&TimedElement> {
+        self.hits(point, tolerance)
+            .max_by(|lhs, rhs| self.paint_order(lhs, rhs))
+    }
+
+    /// Extracts all elements within `radius` of the point, see `Page::hit_test`.
+    ///
+    /// Locked and hidden elements are kept, see `Page::erase_all`.
+    pub fn erase(&mut self, point: Point, radius: i128) -> Vec<TimedElement> {
+        let ids: Vec<ElementId> = self
+            .hits(point, radius)
+            .filter(|element| self.is_selectable(element))
+            .map(|element| element.id())
+            .collect();
+        self.extract_all(ids)
+    }
+
+    /// Like `erase`, but also extracts locked and hidden elements.
+    pub fn erase_all(&mut self, point: Point, radius: i128) -> Vec<TimedElement> {
+        let ids: Vec<ElementId> = self
+            .hits(point, radius)
+            .map(|element| element.id())
+            .collect();
+        self.extract_all(ids)
+    }
+
+    /// Extracts all elements whose envelope lies completely inside the lasso.
+    ///
+    /// Locked and hidden elements are kept, see `Page::lasso_all`.
+    pub fn lasso(&mut self, lasso: &Polygon) -> Vec<TimedElement> {
+        let ids: Vec<ElementId> = self
+            .enclosed(lasso)
+            .filter(|element| self.is_selectable(element))
+            .map(|element| element.id())
+            .collect();
+        self.extract_all(ids)
+    }
+
+    /// Like `lasso`, but also extracts locked and hidden elements.
+    pub fn lasso_all(&mut self, lasso: &Polygon) -> Vec<TimedElement> {
+        let ids: Vec<ElementId> = self.enclosed(lasso).map(|element| element.id()).collect();
+        self.extract_all(ids)
+    }
+
     pub fn len(&self) -> usize {
         self.elements.size()
     }
@@ -41,6 +715,263 @@ impl Page {
     pub fn is_empty(&self) -> bool {
         self.elements.size() == 0
     }
+
+    fn replace_boxed(&mut self, id: ElementId, replacement: Box<dyn Element>) -> bool {
+        let Some(mut element) = self.take(id) else {
+            return false;
+        };
+        self.orphan(&element);
+        let previous = element.clone();
+        element.replace_element(replacement);
+        self.t_index += 1;
+        self.revise(&mut element, previous);
+        self.route(&mut element);
+        self.insert_timed(element);
+        self.reroute_connectors(id);
+        true
+    }
+
+    fn is_selectable(&self, element: &TimedElement) -> bool {
+        !element.is_locked()
+            && !element.is_hidden()
+            && self.layer_is(element, |layer| layer.is_visible() && !layer.is_locked())
+    }
+
+    fn modify_flags(&mut self, id: ElementId, modify: impl FnOnce(&mut TimedElement)) -> bool {
+        let Some(mut element) = self.take(id) else {
+            return false;
+        };
+        let previous = element.clone();
+        modify(&mut element);
+        self.t_index += 1;
+        self.revise(&mut element, previous);
+        self.insert_timed(element);
+        true
+    }
+
+    fn hits(&self, point: Point, tolerance: i128) -> impl Iterator<Item = &TimedElement> {
+        let margin = Vector::new(tolerance, tolerance);
+        let envelope = AABB::from_corners(point - margin, point + margin);
+        self.elements
+            .locate_in_envelope_intersecting(&envelope)
+            .filter(move |element| match element.downcast_ref::<Line>() {
+                Some(line) => {
+                    let points: Vec<Point> = line.iter().copied().collect();
+                    let mut segments = points
+                        .windows(2)
+                        .map(|segment| distance_to_segment(point, segment[0], segment[1]))
+                        .chain(
+                            points
+                                .first()
+                                .map(|first| distance_to_segment(point, *first, *first)),
+                        );
+                    segments.any(|distance| distance <= tolerance as f64)
+                }
+                None => true,
+            })
+    }
+
+    fn enclosed<'a>(&'a self, lasso: &'a Polygon) -> impl Iterator<Item = &'a TimedElement> {
+        self.elements
+            .locate_in_envelope(&lasso.envelope())
+            .filter(|element| encloses(lasso, element))
+    }
+
+    fn extract_all(&mut self, ids: Vec<ElementId>) -> Vec<TimedElement> {
+        ids.into_iter()
+            .filter_map(|id| self.extract_by_id(id))
+            .collect()
+    }
+
+    fn paint_order(&self, lhs: &TimedElement, rhs: &TimedElement) -> std::cmp::Ordering {
+        self.layer_position(lhs.layer())
+            .cmp(&self.layer_position(rhs.layer()))
+            .then(lhs.cmp(rhs))
+    }
+
+    fn layer_position(&self, id: LayerId) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.id() == id)
+    }
+
+    fn layer_is(&self, element: &TimedElement, predicate: impl Fn(&Layer) -> bool) -> bool {
+        self.layer(element.layer()).is_some_and(predicate)
+    }
+
+    fn take(&mut self, id: ElementId) -> Option<TimedElement> {
+        let envelope = *self.envelopes.get(&id)?;
+        let element = self
+            .elements
+            .drain_with_selection_function(SelectByIdFunction::new(envelope, id))
+            .next()?;
+        self.forget(&element);
+        Some(element)
+    }
+
+    /// Finds an element on the page or inside one of its groups.
+    fn find(&self, id: ElementId) -> Option<&TimedElement> {
+        if let Some(element) = self.get(id) {
+            return Some(element);
+        }
+        let group = self.find(*self.parents.get(&id)?)?;
+        group
+            .downcast_ref::<Group>()?
+            .iter()
+            .find(|child| child.id() == id)
+    }
+
+    /// Removes the element from the lookup tables after it was taken out of the r*-tree.
+    fn forget(&mut self, element: &TimedElement) {
+        self.envelopes.remove(&element.id());
+        if let Some(layer) = self.paint_order.get_mut(&element.layer()) {
+            layer.remove(&paint_key(element));
+            if layer.is_empty() {
+                self.paint_order.remove(&element.layer());
+            }
+        }
+        if let Some(connector) = element.downcast_ref::<Connector>() {
+            for referenced in [connector.source().element(), connector.target().element()] {
+                if let Some(connectors) = self.connectors.get_mut(&referenced) {
+                    connectors.remove(&element.id());
+                    if connectors.is_empty() {
+                        self.connectors.remove(&referenced);
+                    }
+                }
+            }
+        }
+    }
+
+    fn touch(&mut self) {
+        self.modified = self.clock.now();
+    }
+
+    fn bury(&mut self, element: &TimedElement) {
+        self.orphan(element);
+        self.t_index += 1;
+        self.entomb(element.clone());
+    }
+
+    /// Marks a modified element as written at the current `t_index` and keeps its previous state
+    /// in history.
+    fn revise(&mut self, element: &mut TimedElement, previous: TimedElement) {
+        self.entomb(previous);
+        element.set_revision(self.t_index);
+        element.set_modified(self.clock.now());
+    }
+
+    /// Keeps the element in history as removed at the current `t_index`.
+    fn entomb(&mut self, element: TimedElement) {
+        self.touch();
+        self.tombstones.push(Tombstone::new(element, self.t_index));
+    }
+
+    /// Forgets the groups of the elements inside an element that leaves the page.
+    fn orphan(&mut self, element: &TimedElement) {
+        let mut descendants = Vec::new();
+        collect_descendants(element, &mut descendants);
+        for descendant in descendants {
+            self.parents.remove(&descendant);
+        }
+    }
+
+    fn insert_timed(&mut self, element: TimedElement) {
+        self.touch();
+        self.envelopes.insert(element.id(), element.envelope());
+        self.paint_order
+            .entry(element.layer())
+            .or_default()
+            .insert(paint_key(&element));
+        if let Some(connector) = element.downcast_ref::<Connector>() {
+            for referenced in [connector.source().element(), connector.target().element()] {
+                self.connectors
+                    .entry(referenced)
+                    .or_default()
+                    .insert(element.id());
+            }
+        }
+        self.elements.insert(element);
+    }
+
+    fn route(&self, element: &mut TimedElement) {
+        if let Some(connector) = element.downcast_mut::<Connector>() {
+            let source = self
+                .find(connector.source().element())
+                .map(|element| element.envelope());
+            let target = self
+                .find(connector.target().element())
+                .map(|element| element.envelope());
+            connector.reroute(source.as_ref(), target.as_ref());
+        }
+    }
+
+    /// Re-routes the connectors attached to the element or to an element inside it.
+    fn reroute_connectors(&mut self, id: ElementId) {
+        let mut ids = Vec::new();
+        if let Some(element) = self.get(id) {
+            collect_descendants(element, &mut ids);
+        }
+        let connectors: BTreeSet<ElementId> = ids
+            .iter()
+            .filter_map(|id| self.connectors.get(id))
+            .flatten()
+            .copied()
+            .collect();
+        for connector in connectors {
+            if let Some(mut element) = self.take(connector) {
+                self.route(&mut element);
+                self.insert_timed(element);
+            }
+        }
+    }
+}
+
+/// Orders like `TimedElement::cmp` within a layer.
+fn paint_key(element: &TimedElement) -> (i32, u128, ElementId) {
+    (element.z_index(), element.t_index(), element.id())
+}
+
+/// Whether the lasso encloses the geometry of the element.
+///
+/// Lines and polygons are tested by their points and segments, groups by their children and all
+/// other elements by the outline of their envelope.
+fn encloses(lasso: &Polygon, element: &TimedElement) -> bool {
+    if let Some(group) = element.downcast_ref::<Group>() {
+        return group.iter().all(|child| encloses(lasso, child));
+    }
+    if let Some(line) = element.downcast_ref::<Line>() {
+        let points: Vec<Point> = line.iter().copied().collect();
+        return lasso.contains_path(&points);
+    }
+    let mut outline: Vec<Point> = match element.downcast_ref::<Polygon>() {
+        Some(polygon) => polygon.iter().copied().collect(),
+        None => {
+            let envelope = element.envelope();
+            let (lower, upper) = (envelope.lower(), envelope.upper());
+            vec![
+                lower,
+                Point::new(upper.x, lower.y),
+                upper,
+                Point::new(lower.x, upper.y),
+            ]
+        }
+    };
+    outline.extend(outline.first().copied());
+    lasso.contains_path(&outline)
+}
+
+/// Collects the id of the element and, for groups, the ids of all elements inside it.
+fn collect_descendants(element: &TimedElement, ids: &mut Vec<ElementId>) {
+    ids.push(element.id());
+    if let Some(group) = element.downcast_ref::<Group>() {
+        for child in group {
+            collect_descendants(child, ids);
+        }
+    }
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Page::with_clock(Arc::new(SystemClock))
+    }
 }
 
 pub struct SelectByAddressFunction<T>
@@ -76,13 +1007,51 @@ where
     }
 }
 
+pub struct SelectByIdFunction {
+    envelope: AABB<Point>,
+    id: ElementId,
+}
+
+impl SelectByIdFunction {
+    pub fn new(envelope: AABB<Point>, id: ElementId) -> Self {
+        Self { envelope, id }
+    }
+}
+
+impl SelectionFunction<TimedElement> for SelectByIdFunction {
+    fn should_unpack_parent(&self, parent_envelope: &AABB<Point>) -> bool {
+        parent_envelope.contains_envelope(&self.envelope)
+    }
+
+    fn should_unpack_leaf(&self, leaf: &TimedElement) -> bool {
+        leaf.id() == self.id
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::clock::mocks::ManualClock;
+    use crate::elements::formula::tests::renderer;
     use crate::elements::rectangle::Rectangle;
-    use crate::{Line, Point};
+    use crate::{
+        Anchor, AnchorPoint, Color, Line, Orientation, Paper, Point, Polygon, Recognizer,
+        StickyNote,
+    };
     use std::ops::Deref;
 
+    #[test]
+    fn pages_have_distinct_ids() {
+        assert_ne!(Page::default().id(), Page::default().id());
+    }
+
+    #[test]
+    fn can_restore_page_id() {
+        let id = PageId::new(Page::default().id().value());
+        assert_eq!(Page::with_id(id).id(), id);
+    }
+
     #[test]
     fn add_element() {
         let mut page = Page::default();
@@ -184,4 +1153,1021 @@ mod tests {
         page.insert(line.clone(), 0);
         assert_eq!(page.is_empty(), false);
     }
+
+    #[test]
+    fn insert_returns_distinct_ids() {
+        let mut page = Page::default();
+        let first = page.insert(Line::default(), 0);
+        let second = page.insert(Line::default(), 0);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn can_get_element_by_id() {
+        let mut page = Page::default();
+        page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 0);
+        let id = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 3);
+        assert_eq!(page.get(id).unwrap().z_index(), 3);
+        assert!(page.get(ElementId::new(42)).is_none());
+    }
+
+    #[test]
+    fn can_extract_element_by_id() {
+        let mut page = Page::default();
+        let line = Line::from_iter([(1, 2), (2, 3)].iter());
+        let id = page.insert(line.clone(), 0);
+
+        let extracted = page.extract_by_id(id).unwrap();
+        assert_eq!(extracted.downcast_ref::<Line>().unwrap(), &line);
+        assert!(page.is_empty());
+        assert!(page.get(id).is_none());
+        assert!(page.extract_by_id(id).is_none());
+    }
+
+    #[test]
+    fn update_keeps_indices_and_refreshes_envelope() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(1, 2)].iter()), 4);
+        let t_index = page.get(id).unwrap().t_index();
+
+        assert!(page.update(id, |line: &mut Line| line.push_back((10, 10).into())));
+
+        let updated = page.get(id).unwrap();
+        assert_eq!(updated.z_index(), 4);
+        assert_eq!(updated.t_index(), t_index);
+        assert_eq!(updated.envelope().upper(), (10, 10));
+        let mut selection =
+            page.locate_in_envelope(AABB::from_corners((0, 0).into(), (10, 10).into()));
+        assert_eq!(selection.next().unwrap().id(), id);
+    }
+
+    #[test]
+    fn update_with_wrong_type_leaves_element_untouched() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(1, 2)].iter()), 0);
+        assert!(!page.update(id, |_: &mut Rectangle| panic!("must not be called")));
+        assert_eq!(page.len(), 1);
+        assert!(page.get(id).is_some());
+    }
+
+    #[test]
+    fn connector_is_routed_on_insert() {
+        let mut page = Page::default();
+        let source = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::MaxX),
+                Anchor::new(target, AnchorPoint::MinX),
+            ),
+            0,
+        );
+
+        let connector = page
+            .get(connector)
+            .unwrap()
+            .downcast_ref::<Connector>()
+            .unwrap();
+        assert_eq!(connector.start(), (2, 1));
+        assert_eq!(connector.end(), (10, 1));
+    }
+
+    #[test]
+    fn connector_is_rerouted_when_element_is_updated() {
+        let mut page = Page::default();
+        let source = page.insert(Line::from_iter([(0, 0), (2, 2)].iter()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+
+        page.update(source, |line: &mut Line| {
+            for point in line.iter_mut() {
+                point.y += 20;
+            }
+        });
+
+        let stored = page.get(connector).unwrap();
+        assert_eq!(stored.envelope().lower(), (1, 1));
+        assert_eq!(stored.envelope().upper(), (11, 21));
+        let found = page
+            .locate_in_envelope(AABB::from_corners((1, 1).into(), (11, 21).into()))
+            .any(|element| element.id() == connector);
+        assert!(found);
+    }
+
+    #[test]
+    fn connector_keeps_endpoint_of_removed_element() {
+        let mut page = Page::default();
+        let source = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+        assert_eq!(page.dangling_connectors().count(), 0);
+
+        page.extract_by_id(source);
+        page.transform_selection(&[target], &Affine::translate(0.0, 10.0));
+
+        let dangling: Vec<ElementId> = page
+            .dangling_connectors()
+            .map(|element| element.id())
+            .collect();
+        assert_eq!(dangling, vec![connector]);
+        let stored = page
+            .get(connector)
+            .unwrap()
+            .downcast_ref::<Connector>()
+            .unwrap();
+        assert_eq!(stored.start(), (1, 1));
+        assert_eq!(stored.end(), (11, 11));
+    }
+
+    #[test]
+    fn erasing_an_endpoint_leaves_connector_dangling() {
+        let mut page = Page::default();
+        let source = page.insert(Line::from_iter([(0, 0), (2, 0)].iter()), 0);
+        let target = page.insert(Rectangle::new((10, 10).into(), (12, 12).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+
+        assert_eq!(page.erase((0, 0).into(), 0).len(), 1);
+
+        assert_eq!(page.dangling_connectors().next().unwrap().id(), connector);
+        assert!(page.extract_by_id(connector).is_some());
+        assert_eq!(page.dangling_connectors().count(), 0);
+    }
+
+    #[test]
+    fn ungrouped_connector_follows_its_endpoints() {
+        let mut page = Page::default();
+        let source = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+        let group = page.group(&[connector, source]).unwrap();
+        page.transform_selection(&[target], &Affine::translate(0.0, 10.0));
+
+        page.ungroup(group).unwrap();
+        assert_eq!(page.get(connector).unwrap().envelope().upper(), (11, 11));
+
+        page.transform_selection(&[source], &Affine::translate(0.0, 20.0));
+        let stored = page.get(connector).unwrap();
+        assert_eq!(stored.envelope().lower(), (1, 11));
+        assert_eq!(stored.envelope().upper(), (11, 21));
+    }
+
+    #[test]
+    fn connector_follows_grouped_endpoint() {
+        let mut page = Page::default();
+        let source = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let other = page.insert(Rectangle::new((0, 4).into(), (2, 6).into()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+        let group = page.group(&[source, other]).unwrap();
+        let outer = page.group(&[group]).unwrap();
+
+        page.transform_selection(&[outer], &Affine::translate(20.0, 0.0));
+
+        assert_eq!(page.dangling_connectors().count(), 0);
+        let start = |page: &Page| {
+            page.get(connector)
+                .unwrap()
+                .downcast_ref::<Connector>()
+                .unwrap()
+                .start()
+        };
+        assert_eq!(start(&page), (21, 1));
+
+        page.ungroup(outer);
+        page.ungroup(group);
+        page.transform_selection(&[source], &Affine::translate(0.0, 10.0));
+        assert_eq!(start(&page), (21, 11));
+    }
+
+    #[test]
+    fn can_group_elements() {
+        let mut page = Page::default();
+        let first = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 2);
+        let second = page.insert(Line::from_iter([(4, 4), (5, 6)].iter()), 5);
+        let other = page.insert(Line::from_iter([(9, 9)].iter()), 0);
+
+        let group = page.group(&[first, second]).unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert!(page.get(first).is_none());
+        assert!(page.get(other).is_some());
+        let stored = page.get(group).unwrap();
+        assert_eq!(stored.z_index(), 5);
+        assert_eq!(stored.envelope().lower(), (0, 0));
+        assert_eq!(stored.envelope().upper(), (5, 6));
+        assert_eq!(stored.downcast_ref::<Group>().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn grouping_unknown_elements_creates_no_group() {
+        let mut page = Page::default();
+        page.insert(Line::default(), 0);
+        assert!(page.group(&[ElementId::new(42)]).is_none());
+        assert_eq!(page.len(), 1);
+    }
+
+    #[test]
+    fn ungroup_restores_children_with_their_indices() {
+        let mut page = Page::default();
+        let first = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 2);
+        let second = page.insert(Line::from_iter([(4, 4), (5, 6)].iter()), 5);
+        let first_t_index = page.get(first).unwrap().t_index();
+        let group = page.group(&[first, second]).unwrap();
+
+        let children = page.ungroup(group).unwrap();
+
+        assert_eq!(children, vec![first, second]);
+        assert_eq!(page.len(), 2);
+        assert!(page.get(group).is_none());
+        let restored = page.get(first).unwrap();
+        assert_eq!(restored.z_index(), 2);
+        assert_eq!(restored.t_index(), first_t_index);
+        assert_eq!(page.get(second).unwrap().z_index(), 5);
+    }
+
+    #[test]
+    fn snapshots_show_elements_as_they_were_grouped() {
+        let mut page = Page::default();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        let second = page.insert(Line::from_iter([(5, 5)].iter()), 0);
+        let before = page.t_index();
+        let group = page.group(&[first, second]).unwrap();
+        let grouped = page.t_index();
+        page.ungroup(group);
+
+        let ids = |snapshot: Snapshot| -> Vec<ElementId> {
+            snapshot.iter().map(|element| element.id()).collect()
+        };
+        assert_eq!(ids(page.snapshot_at(before)), vec![first, second]);
+        assert_eq!(ids(page.snapshot_at(grouped)), vec![group]);
+        assert_eq!(ids(page.snapshot_at(page.t_index())), vec![first, second]);
+        assert_eq!(page.get(first).unwrap().t_index(), first.value());
+        assert_eq!(page.iter_until(page.t_index()).count(), 5);
+    }
+
+    #[test]
+    fn snapshots_show_replaced_elements_before_replacement() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(0, 0), (10, 0)].iter()), 0);
+        let before = page.t_index();
+        page.replace(id, Rectangle::new((0, 0).into(), (10, 10).into()));
+
+        let snapshot = page.snapshot_at(before);
+        let original = snapshot.iter().next().unwrap();
+        assert!(original.downcast_ref::<Line>().is_some());
+        assert_eq!(snapshot.len(), 1);
+        let snapshot = page.snapshot_at(page.t_index());
+        assert!(snapshot.iter().next().unwrap().is::<Rectangle>());
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(page.get(id).unwrap().t_index(), before);
+        assert_eq!(page.get(id).unwrap().revision(), page.t_index());
+    }
+
+    #[test]
+    fn ungroup_ignores_elements_that_are_no_group() {
+        let mut page = Page::default();
+        let id = page.insert(Line::default(), 0);
+        assert!(page.ungroup(id).is_none());
+        assert!(page.get(id).is_some());
+    }
+
+    #[test]
+    fn can_move_group_as_unit() {
+        let mut page = Page::default();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        let second = page.insert(Line::from_iter([(2, 2)].iter()), 0);
+        let group = page.group(&[first, second]).unwrap();
+
+        page.update(group, |group: &mut Group| {
+            for child in group.iter_mut() {
+                for point in child.downcast_mut::<Line>().unwrap().iter_mut() {
+                    point.x += 10;
+                }
+            }
+        });
+
+        let envelope = page.get(group).unwrap().envelope();
+        assert_eq!(envelope.lower(), (10, 0));
+        assert_eq!(envelope.upper(), (12, 2));
+    }
+
+    #[test]
+    fn toggling_sticky_note_reindexes_it() {
+        let mut page = Page::default();
+        let note = StickyNote::new(
+            (0, 0).into(),
+            (100_000, 100_000).into(),
+            Color::rgb(255, 255, 0),
+        );
+        let id = page.insert(note, 0);
+        let far_corner = AABB::from_corners((50_000, 50_000).into(), (100_000, 100_000).into());
+        assert_eq!(page.locate_in_envelope(far_corner).count(), 0);
+
+        page.update(id, |note: &mut StickyNote| note.toggle());
+
+        let collapsed = AABB::from_corners((0, 0).into(), (20_000, 20_000).into());
+        assert_eq!(page.locate_in_envelope(collapsed).next().unwrap().id(), id);
+
+        page.update(id, |note: &mut StickyNote| note.toggle());
+
+        assert_eq!(page.locate_in_envelope(collapsed).count(), 0);
+        assert_eq!(page.get(id).unwrap().envelope().upper(), (100_000, 100_000));
+    }
+
+    #[test]
+    fn renders_stale_formulas_and_reindexes_them() {
+        let mut page = Page::default();
+        let id = page.insert(Formula::new((100, 100).into(), "x^2"), 0);
+        page.insert(Line::from_iter([(0, 0)].iter()), 0);
+
+        assert_eq!(page.render_formulas(&renderer("x^2")), 1);
+        assert_eq!(page.render_formulas(&renderer("x^2")), 0);
+
+        let rendered = AABB::from_corners((100, 98).into(), (110, 104).into());
+        assert_eq!(page.locate_in_envelope(rendered).next().unwrap().id(), id);
+
+        page.update(id, |formula: &mut Formula| formula.set_source("y^2"));
+        assert_eq!(page.render_formulas(&renderer("y^2")), 1);
+    }
+
+    #[test]
+    fn transform_selection_moves_and_reindexes_elements() {
+        let mut page = Page::default();
+        let line = page.insert(Line::from_iter([(0, 0), (2, 2)].iter()), 1);
+        let rectangle = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 2);
+        let untouched = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+
+        let transformed = page.transform_selection(
+            &[line, rectangle, ElementId::new(42)],
+            &Affine::translate(100.0, 0.0),
+        );
+
+        assert_eq!(transformed, 2);
+        let moved = AABB::from_corners((100, 0).into(), (102, 2).into());
+        assert_eq!(page.locate_in_envelope(moved).count(), 2);
+        assert_eq!(page.get(line).unwrap().z_index(), 1);
+        assert_eq!(page.get(untouched).unwrap().envelope().upper(), (0, 0));
+    }
+
+    #[test]
+    fn rotating_rectangle_turns_it_into_polygon() {
+        let mut page = Page::default();
+        let id = page.insert(Rectangle::new((-1, -1).into(), (1, 1).into()), 0);
+
+        page.transform_selection(&[id], &Affine::rotate(std::f64::consts::FRAC_PI_4));
+
+        let rotated = page.get(id).unwrap();
+        assert!(rotated.downcast_ref::<Rectangle>().is_none());
+        assert_eq!(rotated.downcast_ref::<Polygon>().unwrap().len(), 4);
+        assert_eq!(rotated.envelope().lower(), (-1, -1));
+        assert_eq!(rotated.envelope().upper(), (1, 1));
+    }
+
+    #[test]
+    fn rotating_note_is_refused() {
+        let mut page = Page::default();
+        let note = page.insert(
+            StickyNote::new((0, 0).into(), (10, 10).into(), Color::rgb(255, 255, 0)),
+            0,
+        );
+        let line = page.insert(Line::from_iter([(0, 0), (2, 0)].iter()), 0);
+
+        let transformed =
+            page.transform_selection(&[note, line], &Affine::rotate(std::f64::consts::FRAC_PI_2));
+
+        assert_eq!(transformed, 1);
+        let note = page.get(note).unwrap();
+        assert_eq!(note.envelope().lower(), (0, 0));
+        assert_eq!(note.envelope().upper(), (10, 10));
+        assert_eq!(page.get(line).unwrap().envelope().upper(), (0, 2));
+    }
+
+    #[test]
+    fn transform_selection_reroutes_connectors() {
+        let mut page = Page::default();
+        let source = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let target = page.insert(Rectangle::new((10, 0).into(), (12, 2).into()), 0);
+        let connector = page.insert(
+            Connector::new(
+                Anchor::new(source, AnchorPoint::Center),
+                Anchor::new(target, AnchorPoint::Center),
+            ),
+            0,
+        );
+
+        page.transform_selection(&[target], &Affine::translate(0.0, 10.0));
+
+        let connector = page
+            .get(connector)
+            .unwrap()
+            .downcast_ref::<Connector>()
+            .unwrap();
+        assert_eq!(connector.end(), (11, 11));
+    }
+
+    #[test]
+    fn simplifies_lines_on_insert() {
+        let mut page = Page::default();
+        page.insert(Line::from_iter([(0, 0), (1, 0), (2, 0)].iter()), 0);
+        assert_eq!(page.removed_points(), 0);
+
+        page.set_simplify_on_insert(Some(Simplification::RamerDouglasPeucker(0.5)));
+        let id = page.insert(Line::from_iter([(0, 0), (1, 0), (2, 0), (3, 0)].iter()), 0);
+        page.insert(Line::from_iter([(0, 0), (1, 0), (2, 0)].iter()), 0);
+
+        assert_eq!(page.removed_points(), 3);
+        let line = page.get(id).unwrap().downcast_ref::<Line>().unwrap();
+        assert_eq!(line.len(), 2);
+    }
+
+    #[test]
+    fn replace_keeps_indices() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(0, 0), (5, 5)].iter()), 7);
+        let t_index = page.get(id).unwrap().t_index();
+
+        assert!(page.replace(id, Rectangle::new((0, 0).into(), (5, 5).into())));
+
+        let replaced = page.get(id).unwrap();
+        assert!(replaced.downcast_ref::<Rectangle>().is_some());
+        assert_eq!(replaced.z_index(), 7);
+        assert_eq!(replaced.t_index(), t_index);
+        assert_eq!(page.len(), 1);
+        assert!(!page.replace(ElementId::new(42), Line::default()));
+    }
+
+    #[test]
+    fn can_replace_stroke_with_recognized_shape() {
+        let mut page = Page::default();
+        let id = page.insert(
+            Line::from_iter([(0, 0), (50, 1), (100, 0), (100, 60), (0, 60), (1, 1)].iter()),
+            3,
+        );
+        let stroke = page.get(id).unwrap().downcast_ref::<Line>().unwrap();
+        let suggestion = Recognizer::default().recognize(stroke).unwrap();
+
+        assert!(page.replace_with_shape(id, suggestion.into_shape()));
+
+        let replaced = page.get(id).unwrap();
+        assert_eq!(replaced.z_index(), 3);
+        let rectangle = replaced.downcast_ref::<Rectangle>().unwrap();
+        assert_eq!(rectangle.upper(), (100, 60));
+    }
+
+    #[test]
+    fn extract_advances_t_index_and_leaves_tombstone() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        assert_eq!(page.t_index(), 1);
+
+        page.extract_by_id(id).unwrap();
+
+        assert_eq!(page.t_index(), 2);
+        let tombstone = page.tombstones().next().unwrap();
+        assert_eq!(tombstone.element().id(), id);
+        assert_eq!(tombstone.removed_at(), 2);
+    }
+
+    #[test]
+    fn update_keeps_previous_state_as_tombstone() {
+        let mut page = Page::default();
+        let id = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        page.update(id, |line: &mut Line| line.push_back((1, 1).into()));
+        assert_eq!(page.t_index(), 2);
+        assert_eq!(page.get(id).unwrap().revision(), 2);
+        let tombstone = page.tombstones().next().unwrap();
+        assert_eq!(tombstone.removed_at(), 2);
+        assert_eq!(
+            tombstone.element().downcast_ref::<Line>().unwrap(),
+            &Line::from_iter([(0, 0)].iter())
+        );
+
+        assert!(!page.update(id, |_: &mut Rectangle| {}));
+        assert_eq!(page.t_index(), 2);
+        assert_eq!(page.tombstones().count(), 1);
+    }
+
+    #[test]
+    fn snapshot_before_in_place_edits_shows_previous_state() {
+        let mut page = Page::default();
+        let line = page.insert(Line::from_iter([(0, 0), (2, 2)].iter()), 0);
+        let rectangle = page.insert(Rectangle::new((0, 0).into(), (4, 4).into()), 0);
+        let before = page.t_index();
+
+        let moved = page.transform_selection(&[line, rectangle], &Affine::translate(10.0, 0.0));
+        assert_eq!(moved, 2);
+        assert_eq!(page.t_index(), before + 1);
+        page.set_hidden(rectangle, true);
+
+        let snapshot = page.snapshot_at(before);
+        let old_line = snapshot.iter().find(|e| e.id() == line).unwrap();
+        assert_eq!(old_line.envelope().lower(), (0, 0));
+        let old_rectangle = snapshot.iter().find(|e| e.id() == rectangle).unwrap();
+        assert_eq!(old_rectangle.envelope().lower(), (0, 0));
+        assert!(!old_rectangle.is_hidden());
+
+        let moved = page.snapshot_at(before + 1);
+        let moved_rectangle = moved.iter().find(|e| e.id() == rectangle).unwrap();
+        assert_eq!(moved_rectangle.envelope().lower(), (10, 0));
+        assert!(!moved_rectangle.is_hidden());
+        assert_eq!(moved.len(), 2);
+
+        let current = page.snapshot_at(page.t_index());
+        assert!(current
+            .iter()
+            .find(|e| e.id() == rectangle)
+            .unwrap()
+            .is_hidden());
+    }
+
+    #[test]
+    fn snapshot_contains_elements_present_at_t_index() {
+        let mut page = Page::default();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        let second = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        page.extract_by_id(first);
+        let third = page.insert(Line::from_iter([(2, 2)].iter()), 0);
+
+        let ids = |t_index| -> Vec<ElementId> {
+            page.snapshot_at(t_index).iter().map(|e| e.id()).collect()
+        };
+        assert_eq!(ids(0), vec![]);
+        assert_eq!(ids(1), vec![first]);
+        assert_eq!(ids(2), vec![first, second]);
+        assert_eq!(ids(3), vec![second]);
+        assert_eq!(ids(4), vec![second, third]);
+    }
+
+    #[test]
+    fn iter_until_yields_all_writes_in_order() {
+        let mut page = Page::default();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 5);
+        let second = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        page.extract_by_id(first);
+        let third = page.insert(Line::from_iter([(2, 2)].iter()), 0);
+
+        let ids: Vec<ElementId> = page.iter_until(4).map(|e| e.id()).collect();
+        assert_eq!(ids, vec![first, second, third]);
+        let ids: Vec<ElementId> = page.iter_until(1).map(|e| e.id()).collect();
+        assert_eq!(ids, vec![first]);
+    }
+
+    #[test]
+    fn insert_records_creation_time_and_author() {
+        let clock = Arc::new(ManualClock::default());
+        let mut page = Page::default();
+        page.set_clock(clock.clone());
+        page.set_author(Some(AuthorId::new("alice")));
+        clock.set(10);
+        let id = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+
+        let element = page.get(id).unwrap();
+        assert_eq!(element.created(), Some(ManualClock::at(10)));
+        assert_eq!(element.modified(), Some(ManualClock::at(10)));
+        assert_eq!(element.author(), Some(&AuthorId::new("alice")));
+    }
+
+    #[test]
+    fn modifications_update_modification_time() {
+        let clock = Arc::new(ManualClock::default());
+        let mut page = Page::default();
+        page.set_clock(clock.clone());
+        let line = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        let rectangle = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 0);
+
+        clock.set(5);
+        page.update(line, |line: &mut Line| line.push_back((1, 1).into()));
+        clock.set(7);
+        page.transform_selection(&[rectangle], &Affine::translate(1.0, 1.0));
+
+        let line = page.get(line).unwrap();
+        assert_eq!(line.created(), Some(ManualClock::at(0)));
+        assert_eq!(line.modified(), Some(ManualClock::at(5)));
+        assert_eq!(
+            page.get(rectangle).unwrap().modified(),
+            Some(ManualClock::at(7))
+        );
+    }
+
+    #[test]
+    fn finds_elements_added_between_times() {
+        let clock = Arc::new(ManualClock::default());
+        let mut page = Page::default();
+        page.set_clock(clock.clone());
+        let ids: Vec<ElementId> = (0..4)
+            .map(|i| {
+                clock.set(i * 10);
+                page.insert(Line::from_iter([(0, 0)].iter()), 0)
+            })
+            .collect();
+
+        let mut found: Vec<ElementId> = page
+            .added_between(ManualClock::at(10), ManualClock::at(30))
+            .map(|element| element.id())
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![ids[1], ids[2]]);
+    }
+
+    #[test]
+    fn finds_elements_by_author() {
+        let mut page = Page::default();
+        page.set_author(Some(AuthorId::new("alice")));
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        page.set_author(Some(AuthorId::new("bob")));
+        page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        page.set_author(None);
+        page.insert(Line::from_iter([(2, 2)].iter()), 0);
+
+        let alice = AuthorId::new("alice");
+        let found: Vec<ElementId> = page.by_author(&alice).map(|e| e.id()).collect();
+        assert_eq!(found, vec![first]);
+    }
+
+    #[test]
+    fn iter_paint_order_sorts_by_z_index_then_t_index() {
+        let mut page = Page::default();
+        let top = page.insert(Line::from_iter([(0, 0)].iter()), 2);
+        let bottom = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        let middle_early = page.insert(Line::from_iter([(2, 2)].iter()), 1);
+        let middle_late = page.insert(Line::from_iter([(3, 3)].iter()), 1);
+
+        let ids: Vec<ElementId> = page.iter_paint_order().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![bottom, middle_early, middle_late, top]);
+    }
+
+    #[test]
+    fn has_one_default_layer() {
+        let mut page = Page::default();
+        let layers: Vec<&str> = page.layers().map(|layer| layer.name()).collect();
+        assert_eq!(layers, vec!["Default"]);
+        let id = page.insert(Line::default(), 0);
+        assert_eq!(page.get(id).unwrap().layer(), page.active_layer());
+        assert!(!page.remove_layer(page.active_layer()));
+    }
+
+    #[test]
+    fn inserts_into_active_layer() {
+        let mut page = Page::default();
+        let sketch = page.add_layer("Sketch");
+        assert!(page.set_active_layer(sketch));
+        let id = page.insert(Line::default(), 0);
+        assert_eq!(page.get(id).unwrap().layer(), sketch);
+        assert!(!page.set_active_layer(LayerId::new(42)));
+    }
+
+    #[test]
+    fn paint_order_follows_layer_order() {
+        let mut page = Page::default();
+        let background = page.active_layer();
+        let bottom = page.insert(Line::from_iter([(0, 0)].iter()), 10);
+        let foreground = page.add_layer("Foreground");
+        page.set_active_layer(foreground);
+        let top = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+
+        let ids =
+            |page: &Page| -> Vec<ElementId> { page.iter_paint_order().map(|e| e.id()).collect() };
+        assert_eq!(ids(&page), vec![bottom, top]);
+
+        assert!(page.move_layer(foreground, 0));
+        assert_eq!(ids(&page), vec![top, bottom]);
+        let layers: Vec<LayerId> = page.layers().map(|layer| layer.id()).collect();
+        assert_eq!(layers, vec![foreground, background]);
+        assert_eq!(page.get(bottom).unwrap().z_index(), 10);
+    }
+
+    #[test]
+    fn paint_order_index_follows_changes() {
+        let mut page = Page::default();
+        let background = page.active_layer();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 1);
+        let second = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        let foreground = page.add_layer("Foreground");
+        let ids =
+            |page: &Page| -> Vec<ElementId> { page.iter_paint_order().map(|e| e.id()).collect() };
+        assert_eq!(ids(&page), vec![second, first]);
+
+        assert_eq!(page.move_to_layer(&[second], foreground), 1);
+        page.transform_selection(&[first], &Affine::translate(1.0, 0.0));
+        assert_eq!(ids(&page), vec![first, second]);
+
+        page.extract_by_id(first);
+        assert_eq!(ids(&page), vec![second]);
+        assert!(page.remove_layer(foreground));
+        assert!(ids(&page).is_empty());
+        assert!(!page.remove_layer(background));
+    }
+
+    #[test]
+    fn queries_respect_layer_visibility_and_lock() {
+        let mut page = Page::default();
+        let background = page.active_layer();
+        let hidden = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let sketch = page.add_layer("Sketch");
+        page.set_active_layer(sketch);
+        let visible = page.insert(Rectangle::new((1, 1).into(), (3, 3).into()), 0);
+
+        page.layer_mut(background).unwrap().set_visible(false);
+        let ids: Vec<ElementId> = page.iter_visible().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![visible]);
+
+        let envelope = AABB::from_corners((0, 0).into(), (4, 4).into());
+        let selectable = |page: &Page| -> Vec<ElementId> {
+            page.locate_in_envelope(envelope).map(|e| e.id()).collect()
+        };
+        assert_eq!(selectable(&page), vec![visible]);
+        page.layer_mut(sketch).unwrap().set_locked(true);
+        assert!(selectable(&page).is_empty());
+        assert_eq!(page.locate_all_in_envelope(envelope).count(), 2);
+        assert!(page.get(hidden).is_some());
+    }
+
+    #[test]
+    fn move_to_layer_reassigns_elements() {
+        let mut page = Page::default();
+        let id = page.insert(Line::default(), 0);
+        let sketch = page.add_layer("Sketch");
+        assert_eq!(page.move_to_layer(&[id, ElementId::new(42)], sketch), 1);
+        assert_eq!(page.get(id).unwrap().layer(), sketch);
+        assert_eq!(page.move_to_layer(&[id], LayerId::new(42)), 0);
+    }
+
+    #[test]
+    fn removing_layer_extracts_its_elements() {
+        let mut page = Page::default();
+        let kept = page.insert(Line::default(), 0);
+        let sketch = page.add_layer("Sketch");
+        page.set_active_layer(sketch);
+        let removed = page.insert(Line::default(), 0);
+
+        assert!(page.remove_layer(sketch));
+        assert!(page.get(removed).is_none());
+        assert!(page.get(kept).is_some());
+        assert_eq!(page.tombstones().count(), 1);
+        assert_eq!(page.active_layer(), LayerId::default());
+    }
+
+    #[test]
+    fn group_is_placed_on_topmost_layer_of_children() {
+        let mut page = Page::default();
+        let first = page.insert(Line::from_iter([(0, 0)].iter()), 0);
+        let sketch = page.add_layer("Sketch");
+        page.set_active_layer(sketch);
+        let second = page.insert(Line::from_iter([(1, 1)].iter()), 0);
+        page.set_active_layer(LayerId::default());
+
+        let group = page.group(&[first, second]).unwrap();
+        assert_eq!(page.get(group).unwrap().layer(), sketch);
+        page.ungroup(group);
+        assert_eq!(page.get(first).unwrap().layer(), sketch);
+    }
+
+    #[test]
+    fn locked_and_hidden_elements_are_skipped_by_default() {
+        let mut page = Page::default();
+        let locked = page.insert(Rectangle::new((0, 0).into(), (2, 2).into()), 0);
+        let hidden = page.insert(Rectangle::new((1, 1).into(), (3, 3).into()), 0);
+        let free = page.insert(Rectangle::new((2, 2).into(), (4, 4).into()), 0);
+        assert!(page.set_locked(locked, true));
+        assert!(page.set_hidden(hidden, true));
+        assert!(!page.set_locked(ElementId::new(42), true));
+
+        let envelope = AABB::from_corners((0, 0).into(), (4, 4).into());
+        let ids: Vec<ElementId> = page.locate_in_envelope(envelope).map(|e| e.id()).collect();
+        assert_eq!(ids, vec![free]);
+        assert_eq!(page.locate_all_in_envelope(envelope).count(), 3);
+        let ids: Vec<ElementId> = page.iter_visible().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![locked, free]);
+    }
+
+    #[test]
+    fn hit_test_returns_topmost_unlocked_element() {
+        let mut page = Page::default();
+        let bottom = page.insert(Rectangle::new((0, 0).into(), (10, 10).into()), 0);
+        let top = page.insert(Rectangle::new((0, 0).into(), (10, 10).into()), 1);
+        assert_eq!(page.hit_test((5, 5).into(), 0).unwrap().id(), top);
+
+        page.set_locked(top, true);
+        assert_eq!(page.hit_test((5, 5).into(), 0).unwrap().id(), bottom);
+        assert_eq!(page.hit_test_all((5, 5).into(), 0).unwrap().id(), top);
+        assert!(page.hit_test((20, 20).into(), 5).is_none());
+    }
+
+    #[test]
+    fn hit_test_follows_line_strokes() {
+        let mut page = Page::default();
+        let line = page.insert(Line::from_iter([(0, 0), (10, 10)].iter()), 0);
+        assert!(page.hit_test((10, 0).into(), 2).is_none());
+        assert_eq!(page.hit_test((6, 5).into(), 1).unwrap().id(), line);
+    }
+
+    #[test]
+    fn eraser_keeps_locked_elements() {
+        let mut page = Page::default();
+        let template = page.insert(Line::from_iter([(0, 5), (20, 5)].iter()), 0);
+        let stroke = page.insert(Line::from_iter([(5, 0), (5, 10)].iter()), 0);
+        page.set_locked(template, true);
+
+        let erased: Vec<ElementId> = page
+            .erase((5, 5).into(), 1)
+            .iter()
+            .map(|e| e.id())
+            .collect();
+        assert_eq!(erased, vec![stroke]);
+        assert!(page.get(template).is_some());
+        assert_eq!(
+            page.tombstones()
+                .filter(|tombstone| tombstone.element().id() == stroke)
+                .count(),
+            1
+        );
+
+        let erased: Vec<ElementId> = page
+            .erase_all((5, 5).into(), 1)
+            .iter()
+            .map(|e| e.id())
+            .collect();
+        assert_eq!(erased, vec![template]);
+        assert!(page.is_empty());
+    }
+
+    #[test]
+    fn lasso_extracts_enclosed_elements() {
+        let mut page = Page::default();
+        let inside = page.insert(Rectangle::new((2, 2).into(), (4, 4).into()), 0);
+        let partially = page.insert(Rectangle::new((8, 8).into(), (12, 12).into()), 0);
+        let locked = page.insert(Rectangle::new((5, 5).into(), (6, 6).into()), 0);
+        page.set_locked(locked, true);
+        let lasso = Polygon::from_iter([(0, 0), (10, 0), (10, 10), (0, 10)].iter());
+
+        let ids: Vec<ElementId> = page.lasso(&lasso).iter().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![inside]);
+        let ids: Vec<ElementId> = page.lasso_all(&lasso).iter().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![locked]);
+        assert!(page.get(partially).is_some());
+    }
+
+    #[test]
+    fn lasso_checks_geometry_against_concave_outline() {
+        let mut page = Page::default();
+        let arm = page.insert(Line::from_iter([(1, 1), (1, 8)].iter()), 0);
+        let bridge = page.insert(Line::from_iter([(2, 8), (8, 8)].iter()), 0);
+        let diagonal = page.insert(Line::from_iter([(1, 1), (9, 1), (9, 9)].iter()), 0);
+        let notch = page.insert(Rectangle::new((4, 7).into(), (6, 9).into()), 0);
+        let lasso = Polygon::from_iter([(0, 0), (10, 0), (10, 10), (5, 5), (0, 10)].iter());
+
+        let mut ids: Vec<ElementId> = page.lasso(&lasso).iter().map(|e| e.id()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![arm, diagonal]);
+        assert!(page.get(bridge).is_some());
+        assert!(page.get(notch).is_some());
+    }
+
+    #[test]
+    fn finds_content_overflowing_fixed_pages() {
+        let mut page = Page::default();
+        let inside = page.insert(Rectangle::new((0, 0).into(), (1000, 1000).into()), 0);
+        let outside = page.insert(Rectangle::new((200000, 0).into(), (220000, 1000).into()), 0);
+        assert_eq!(page.geometry(), PageGeometry::Infinite);
+        assert_eq!(page.overflowing().count(), 0);
+
+        page.set_geometry(PageGeometry::paper(Paper::A4, Orientation::Portrait));
+        let ids: Vec<ElementId> = page.overflowing().map(|e| e.id()).collect();
+        assert_eq!(ids, vec![outside]);
+
+        page.set_geometry(PageGeometry::paper(Paper::A4, Orientation::Landscape));
+        assert_eq!(page.overflowing().count(), 0);
+        assert!(page.get(inside).is_some());
+    }
+
+    #[test]
+    fn background_is_clipped_to_page_geometry() {
+        let mut page = Page::default();
+        assert_eq!(page.background(), Background::Plain);
+        page.set_background(Background::Ruled {
+            spacing: 100000,
+            margin: None,
+        });
+        let viewport = AABB::from_corners((0, 0).into(), (1000000, 1000000).into());
+        assert_eq!(page.background_marks(viewport).len(), 11);
+
+        page.set_geometry(PageGeometry::paper(Paper::A5, Orientation::Portrait));
+        let marks = page.background_marks(viewport);
+        assert_eq!(marks.len(), 3);
+        assert_eq!(
+            marks[2],
+            BackgroundMark::Line((0, 200000).into(), (148000, 200000).into())
+        );
+    }
+
+    #[test]
+    fn stores_float_input_and_queries_with_float_envelopes() {
+        let mut page = Page::default();
+        let stroke = [FloatPoint::new(0.4, 0.6), FloatPoint::new(10.5, 3.2)];
+        let line = page.insert(Line::from_iter(stroke), 0);
+        let rectangle = page.insert(
+            Rectangle::new(
+                FloatPoint::new(20.2, 20.2).into(),
+                FloatPoint::new(30.7, 30.7).into(),
+            ),
+            0,
+        );
+
+        let points: Vec<Point> = page
+            .get(line)
+            .unwrap()
+            .downcast_ref::<Line>()
+            .unwrap()
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(points, vec![Point::new(0, 1), Point::new(11, 3)]);
+
+        let envelope = AABB::from_corners(FloatPoint::new(0.5, 0.5), FloatPoint::new(19.5, 19.5));
+        let ids: Vec<ElementId> = page
+            .locate_in_float_envelope(envelope)
+            .map(|e| e.id())
+            .collect();
+        assert_eq!(ids, vec![line]);
+        assert!(page.get(rectangle).is_some());
+    }
+
+    #[test]
+    fn stores_title_and_tags() {
+        let mut page = Page::default();
+        assert_eq!(page.title(), "");
+        page.set_title("Lecture 1");
+        assert_eq!(page.title(), "Lecture 1");
+
+        assert!(page.add_tag("physics"));
+        assert!(page.add_tag("exam"));
+        assert!(!page.add_tag("exam"));
+        assert_eq!(page.tags().collect::<Vec<_>>(), vec!["exam", "physics"]);
+        assert!(page.remove_tag("exam"));
+        assert!(!page.remove_tag("exam"));
+        assert!(page.has_tag("physics"));
+    }
+
+    #[test]
+    fn changes_update_modification_date() {
+        let clock = Arc::new(ManualClock::default());
+        clock.set(1);
+        let mut page = Page::with_clock(clock.clone());
+        assert_eq!(page.created(), ManualClock::at(1));
+        assert_eq!(page.modified(), ManualClock::at(1));
+
+        clock.set(2);
+        let id = page.insert(Line::default(), 0);
+        assert_eq!(page.modified(), ManualClock::at(2));
+        clock.set(3);
+        page.extract_by_id(id);
+        assert_eq!(page.modified(), ManualClock::at(3));
+        clock.set(4);
+        page.add_tag("draft");
+        assert_eq!(page.modified(), ManualClock::at(4));
+        assert_eq!(page.created(), ManualClock::at(1));
+    }
+
+    #[test]
+    fn geometry_and_background_update_modification_date() {
+        let clock = Arc::new(ManualClock::default());
+        let mut page = Page::with_clock(clock.clone());
+
+        clock.set(1);
+        page.set_geometry(PageGeometry::paper(Paper::A4, Orientation::Portrait));
+        assert_eq!(page.modified(), ManualClock::at(1));
+        clock.set(2);
+        page.set_background(Background::Isometric { spacing: 100 });
+        assert_eq!(page.modified(), ManualClock::at(2));
+    }
 }
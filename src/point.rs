@@ -1,4 +1,7 @@
 use rstar::Point as RStarPoint;
+use std::ops::{Add, Sub};
+
+use crate::Vector;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
 pub struct Point {
@@ -10,6 +13,87 @@ impl Point {
     pub fn new(x: i128, y: i128) -> Self {
         Point { x, y }
     }
+
+    /// The vector from the origin to this point.
+    pub fn to_vector(&self) -> Vector {
+        Vector::new(self.x, self.y)
+    }
+
+    pub fn distance(&self, other: Point) -> f64 {
+        (other - *self).length()
+    }
+
+    /// The point halfway to `other`, rounded towards `self`.
+    pub fn midpoint(&self, other: Point) -> Point {
+        *self + (other - *self) / 2
+    }
+
+    /// Interpolates linearly, where `t = 0` yields `self` and `t = 1` yields `other`.
+    pub fn lerp(&self, other: Point, t: f64) -> Point {
+        FloatPoint::new(
+            self.x as f64 + (other.x - self.x) as f64 * t,
+            self.y as f64 + (other.y - self.y) as f64 * t,
+        )
+        .round()
+    }
+
+    pub fn checked_add(&self, vector: Vector) -> Option<Point> {
+        Some(Point::new(
+            self.x.checked_add(vector.x)?,
+            self.y.checked_add(vector.y)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: Point) -> Option<Vector> {
+        Some(Vector::new(
+            self.x.checked_sub(other.x)?,
+            self.y.checked_sub(other.y)?,
+        ))
+    }
+
+    /// Like `midpoint`, but returns `None` if the distance between the points overflows.
+    pub fn checked_midpoint(&self, other: Point) -> Option<Point> {
+        self.checked_add(other.checked_sub(*self)? / 2)
+    }
+
+    /// Like `lerp`, but returns `None` if the distance between the points overflows or the
+    /// result is not representable.
+    pub fn checked_lerp(&self, other: Point, t: f64) -> Option<Point> {
+        let offset = other.checked_sub(*self)?;
+        let coordinate = |start: i128, offset: i128| {
+            let value = (start as f64 + offset as f64 * t).round();
+            (value.is_finite() && value >= i128::MIN as f64 && value < i128::MAX as f64)
+                .then_some(value as i128)
+        };
+        Some(Point::new(
+            coordinate(self.x, offset.x)?,
+            coordinate(self.y, offset.y)?,
+        ))
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, vector: Vector) -> Point {
+        Point::new(self.x + vector.x, self.y + vector.y)
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, vector: Vector) -> Point {
+        Point::new(self.x - vector.x, self.y - vector.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
 }
 
 impl<T> PartialEq<(T, T)> for Point
@@ -73,6 +157,110 @@ where
     }
 }
 
+/// A point with floating-point coordinates in model units.
+///
+/// Use it for sub-unit precision, e.g. for stylus input or chained transformations. `Line` and
+/// `Rectangle` keep the sub-unit remainder of their points, other elements store `Point`s. The
+/// conversion to `Point` rounds to the nearest model unit.
+#[derive(PartialEq, PartialOrd, Debug, Default, Clone, Copy)]
+pub struct FloatPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl FloatPoint {
+    pub fn new(x: f64, y: f64) -> Self {
+        FloatPoint { x, y }
+    }
+
+    pub fn round(&self) -> Point {
+        Point::new(self.x.round() as i128, self.y.round() as i128)
+    }
+
+    pub fn floor(&self) -> Point {
+        Point::new(self.x.floor() as i128, self.y.floor() as i128)
+    }
+
+    pub fn ceil(&self) -> Point {
+        Point::new(self.x.ceil() as i128, self.y.ceil() as i128)
+    }
+
+    /// Splits into the nearest point and the sub-unit remainder, see `from_parts`.
+    ///
+    /// Non-finite coordinates are clamped like `round`, NaN to 0 and infinities to the largest
+    /// coordinates, and get a remainder of 0. The remainder is therefore always finite.
+    pub fn split(&self) -> (Point, FloatPoint) {
+        let point = self.round();
+        let remainder = |value: f64, rounded: i128| {
+            let remainder = value - rounded as f64;
+            if remainder.is_finite() {
+                remainder
+            } else {
+                0.0
+            }
+        };
+        (
+            point,
+            FloatPoint::new(remainder(self.x, point.x), remainder(self.y, point.y)),
+        )
+    }
+
+    /// Joins a point and a sub-unit remainder returned by `split`.
+    pub fn from_parts(point: Point, remainder: FloatPoint) -> Self {
+        FloatPoint::new(point.x as f64 + remainder.x, point.y as f64 + remainder.y)
+    }
+}
+
+impl RStarPoint for FloatPoint {
+    type Scalar = f64;
+    const DIMENSIONS: usize = 2;
+
+    fn generate(mut generator: impl FnMut(usize) -> Self::Scalar) -> Self {
+        FloatPoint {
+            x: generator(0),
+            y: generator(1),
+        }
+    }
+
+    fn nth(&self, index: usize) -> Self::Scalar {
+        if index == 0 {
+            return self.x;
+        }
+        self.y
+    }
+
+    fn nth_mut(&mut self, index: usize) -> &mut Self::Scalar {
+        if index == 0 {
+            return &mut self.x;
+        }
+        &mut self.y
+    }
+}
+
+impl From<Point> for FloatPoint {
+    fn from(point: Point) -> Self {
+        FloatPoint {
+            x: point.x as f64,
+            y: point.y as f64,
+        }
+    }
+}
+
+impl From<FloatPoint> for Point {
+    fn from(point: FloatPoint) -> Self {
+        point.round()
+    }
+}
+
+impl From<(f64, f64)> for FloatPoint {
+    fn from(value: (f64, f64)) -> Self {
+        FloatPoint {
+            x: value.0,
+            y: value.1,
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -128,4 +316,78 @@ mod tests {
         assert_eq!(point.x, 1);
         assert_eq!(point.y, 2);
     }
+
+    #[test]
+    fn points_and_vectors() {
+        let point = Point::new(1, 2);
+        assert_eq!(point + Vector::new(2, 3), (3, 5));
+        assert_eq!(point - Vector::new(2, 3), (-1, -1));
+        assert_eq!(Point::new(4, 6) - point, Vector::new(3, 4));
+        assert_eq!(point.to_vector(), Vector::new(1, 2));
+    }
+
+    #[test]
+    fn distance_midpoint_and_lerp() {
+        let lower = Point::new(0, 0);
+        let upper = Point::new(3, 4);
+        assert_eq!(lower.distance(upper), 5.0);
+        assert_eq!(lower.midpoint(upper), (1, 2));
+        assert_eq!(lower.lerp(upper, 0.5), (2, 2));
+        assert_eq!(lower.lerp(upper, 1.0), upper);
+    }
+
+    #[test]
+    fn checked_arithmetic_detects_overflow() {
+        let point = Point::new(i128::MAX, 0);
+        assert_eq!(point.checked_add(Vector::new(1, 0)), None);
+        assert_eq!(Point::new(i128::MIN, 0).checked_sub(Point::new(1, 0)), None);
+        assert_eq!(
+            point.checked_add(Vector::new(-1, 1)),
+            Some(Point::new(i128::MAX - 1, 1))
+        );
+    }
+
+    #[test]
+    fn checked_midpoint_and_lerp_detect_overflow() {
+        let lower = Point::new(i128::MIN, 0);
+        let upper = Point::new(i128::MAX, 0);
+        assert_eq!(lower.checked_midpoint(upper), None);
+        assert_eq!(lower.checked_lerp(upper, 0.5), None);
+        let origin = Point::new(0, 0);
+        assert_eq!(origin.checked_lerp(upper, 2.0), None);
+        assert_eq!(origin.checked_lerp(upper, f64::NAN), None);
+        let upper = Point::new(3, 4);
+        assert_eq!(origin.checked_midpoint(upper), Some(Point::new(1, 2)));
+        assert_eq!(origin.checked_lerp(upper, 0.5), Some(Point::new(2, 2)));
+    }
+
+    #[test]
+    fn float_point_splits_into_point_and_remainder() {
+        let point = FloatPoint::new(1.25, -2.75);
+        let (rounded, remainder) = point.split();
+        assert_eq!(rounded, (1, -3));
+        assert_eq!(remainder, FloatPoint::new(0.25, 0.25));
+        assert_eq!(FloatPoint::from_parts(rounded, remainder), point);
+    }
+
+    #[test]
+    fn float_point_rounds_to_nearest_unit() {
+        let point = FloatPoint::new(1.5, -2.4);
+        assert_eq!(Point::from(point), (2, -2));
+        assert_eq!(point.floor(), (1, -3));
+        assert_eq!(point.ceil(), (2, -2));
+        assert_eq!(
+            FloatPoint::from(Point::new(3, 4)),
+            FloatPoint::new(3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn float_point_supports_rtree_envelopes() {
+        use rstar::{Envelope, AABB};
+        let envelope =
+            AABB::from_points([FloatPoint::new(0.25, 0.5), FloatPoint::new(1.75, 2.0)].iter());
+        assert!(envelope.contains_point(&FloatPoint::new(1.0, 1.0)));
+        assert_eq!(envelope.area(), 2.25);
+    }
 }
@@ -0,0 +1,432 @@
+use std::time::Duration;
+
+use crate::{ElementId, Line, Page, TimedElement};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DrawEvent<'a> {
+    /// The first `points` points of a line are drawn.
+    Progress {
+        element: &'a TimedElement,
+        points: usize,
+    },
+    /// The element is drawn completely.
+    Complete(&'a TimedElement),
+    /// The element was extracted from the page.
+    Erase(&'a TimedElement),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Visible<'a> {
+    pub element: &'a TimedElement,
+    /// The number of points drawn so far, or `None` if the element is drawn completely.
+    pub drawn_points: Option<usize>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum State {
+    Hidden,
+    Partial(usize),
+    Complete,
+    Erased,
+}
+
+#[derive(Debug)]
+struct Entry<'a> {
+    element: &'a TimedElement,
+    start: Duration,
+    /// The time each point is drawn relative to `start`, only for lines.
+    points: Option<Vec<Duration>>,
+    end: Duration,
+    erased_at: Option<Duration>,
+}
+
+impl Entry<'_> {
+    fn state_at(&self, time: Duration) -> State {
+        if self.erased_at.is_some_and(|erased_at| erased_at <= time) {
+            return State::Erased;
+        }
+        if time < self.start {
+            return State::Hidden;
+        }
+        if time >= self.end {
+            return State::Complete;
+        }
+        match &self.points {
+            Some(points) => {
+                let elapsed = time - self.start;
+                State::Partial(
+                    points
+                        .iter()
+                        .take_while(|offset| **offset <= elapsed)
+                        .count(),
+                )
+            }
+            None => State::Complete,
+        }
+    }
+}
+
+/// Replays how a page was written.
+///
+/// Elements are replayed in the order of their `t_index`, one after another. Lines are drawn
+/// point by point, using their timestamps if present and `point_interval` otherwise. Other
+/// elements appear at once and take `element_duration`. Extracted elements disappear at the time
+/// of their extraction.
+///
+/// Times passed to and returned from the replay are on the recording's time line; `advance`
+/// converts wall-clock time using the playback speed.
+pub struct Replay<'a> {
+    entries: Vec<Entry<'a>>,
+    states: Vec<State>,
+    position: Duration,
+    duration: Duration,
+    speed: f64,
+}
+
+impl<'a> Replay<'a> {
+    pub const DEFAULT_POINT_INTERVAL: Duration = Duration::from_millis(10);
+    pub const DEFAULT_ELEMENT_DURATION: Duration = Duration::from_millis(250);
+
+    pub fn new(page: &'a Page) -> Self {
+        Self::with_timing(
+            page,
+            Self::DEFAULT_POINT_INTERVAL,
+            Self::DEFAULT_ELEMENT_DURATION,
+        )
+    }
+
+    pub fn with_timing(
+        page: &'a Page,
+        point_interval: Duration,
+        element_duration: Duration,
+    ) -> Self {
+        enum Write<'a> {
+            Draw(&'a TimedElement),
+            Erase(ElementId),
+        }
+        let mut writes: Vec<(u128, Write)> = page
+            .iter_until(page.t_index())
+            .map(|element| (element.revision(), Write::Draw(element)))
+            .chain(page.tombstones().map(|tombstone| {
+                (
+                    tombstone.removed_at(),
+                    Write::Erase(tombstone.element().id()),
+                )
+            }))
+            .collect();
+        // A modified element is erased in its previous state before it is drawn again.
+        writes.sort_by_key(|(t_index, write)| (*t_index, matches!(write, Write::Draw(_))));
+
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut cursor = Duration::ZERO;
+        for (_, write) in writes {
+            match write {
+                Write::Draw(element) => {
+                    let points = element
+                        .downcast_ref::<Line>()
+                        .map(|line| point_offsets(line, point_interval));
+                    let length = match &points {
+                        Some(points) => points.last().copied().unwrap_or_default(),
+                        None => element_duration,
+                    };
+                    entries.push(Entry {
+                        element,
+                        start: cursor,
+                        points,
+                        end: cursor + length,
+                        erased_at: None,
+                    });
+                    cursor += length;
+                }
+                Write::Erase(id) => {
+                    if let Some(entry) = entries
+                        .iter_mut()
+                        .find(|entry| entry.element.id() == id && entry.erased_at.is_none())
+                    {
+                        entry.erased_at = Some(cursor);
+                    }
+                }
+            }
+        }
+
+        let states = vec![State::Hidden; entries.len()];
+        let mut replay = Replay {
+            entries,
+            states,
+            position: Duration::ZERO,
+            duration: cursor,
+            speed: 1.0,
+        };
+        replay.seek(Duration::ZERO);
+        replay
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.duration
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Sets the playback speed, where `2.0` plays twice as fast as recorded.
+    ///
+    /// The speed is clamped to a finite, non-negative value; NaN stops playback.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = if speed.is_nan() {
+            0.0
+        } else {
+            speed.clamp(0.0, f64::MAX)
+        };
+    }
+
+    /// Jumps to the given time without emitting events. Use `frame` to redraw afterwards.
+    pub fn seek(&mut self, time: Duration) {
+        self.position = time.min(self.duration);
+        for (entry, state) in self.entries.iter().zip(self.states.iter_mut()) {
+            *state = entry.state_at(self.position);
+        }
+    }
+
+    /// Advances playback by the given wall-clock time and returns what has to be drawn since the
+    /// last call.
+    pub fn advance(&mut self, elapsed: Duration) -> Vec<DrawEvent<'a>> {
+        // Saturates instead of panicking when the scaled time does not fit into a `Duration`.
+        let scaled = Duration::try_from_secs_f64(elapsed.as_secs_f64() * self.speed)
+            .unwrap_or(Duration::MAX);
+        let position = self.position.saturating_add(scaled).min(self.duration);
+        self.position = position;
+
+        let mut events = Vec::new();
+        for (entry, state) in self.entries.iter().zip(self.states.iter_mut()) {
+            let next = entry.state_at(position);
+            if next == *state {
+                continue;
+            }
+            let element = entry.element;
+            match next {
+                State::Partial(points) => events.push(DrawEvent::Progress { element, points }),
+                State::Complete => events.push(DrawEvent::Complete(element)),
+                State::Erased => {
+                    if *state != State::Complete {
+                        events.push(DrawEvent::Complete(element));
+                    }
+                    events.push(DrawEvent::Erase(element));
+                }
+                State::Hidden => {}
+            }
+            *state = next;
+        }
+        events
+    }
+
+    /// Returns everything that is visible at the current position, in drawing order.
+    pub fn frame(&self) -> Vec<Visible<'a>> {
+        self.frame_at(self.position)
+    }
+
+    /// Returns everything that is visible at the given time, in drawing order.
+    pub fn frame_at(&self, time: Duration) -> Vec<Visible<'a>> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry.state_at(time) {
+                State::Partial(points) => Some(Visible {
+                    element: entry.element,
+                    drawn_points: Some(points),
+                }),
+                State::Complete => Some(Visible {
+                    element: entry.element,
+                    drawn_points: None,
+                }),
+                State::Hidden | State::Erased => None,
+            })
+            .collect()
+    }
+}
+
+fn point_offsets(line: &Line, point_interval: Duration) -> Vec<Duration> {
+    match line.timestamps() {
+        Some(timestamps) => {
+            let timestamps: Vec<Duration> = timestamps.copied().collect();
+            let first = timestamps.first().copied().unwrap_or_default();
+            timestamps
+                .into_iter()
+                .map(|timestamp| timestamp.saturating_sub(first))
+                .collect()
+        }
+        None => (0..line.len() as u32)
+            .map(|index| point_interval * index)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Affine, Point, Rectangle};
+    use rstar::RTreeObject;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn page() -> (Page, ElementId, ElementId) {
+        let mut page = Page::default();
+        let mut line = Line::from_iter([(0, 0), (1, 1), (2, 2)].iter());
+        line.set_timestamps([ms(0), ms(100), ms(400)]);
+        let line = page.insert(line, 0);
+        let rectangle = page.insert(Rectangle::new((0, 0).into(), (1, 1).into()), 0);
+        (page, line, rectangle)
+    }
+
+    fn ids(frame: &[Visible]) -> Vec<(ElementId, Option<usize>)> {
+        frame
+            .iter()
+            .map(|visible| (visible.element.id(), visible.drawn_points))
+            .collect()
+    }
+
+    #[test]
+    fn duration_covers_all_elements() {
+        let (page, _, _) = page();
+        let replay = Replay::with_timing(&page, ms(10), ms(200));
+        assert_eq!(replay.duration(), ms(600));
+    }
+
+    #[test]
+    fn lines_without_timestamps_use_point_interval() {
+        let mut page = Page::default();
+        page.insert(Line::from_iter([(0, 0), (1, 1), (2, 2)].iter()), 0);
+        let replay = Replay::with_timing(&page, ms(10), ms(200));
+        assert_eq!(replay.duration(), ms(20));
+    }
+
+    #[test]
+    fn frame_shows_partially_drawn_lines() {
+        let (page, line, rectangle) = page();
+        let replay = Replay::with_timing(&page, ms(10), ms(200));
+        assert_eq!(ids(&replay.frame_at(ms(0))), vec![(line, Some(1))]);
+        assert_eq!(ids(&replay.frame_at(ms(150))), vec![(line, Some(2))]);
+        assert_eq!(
+            ids(&replay.frame_at(ms(400))),
+            vec![(line, None), (rectangle, None)]
+        );
+    }
+
+    #[test]
+    fn advance_emits_incremental_events() {
+        let (page, line, rectangle) = page();
+        let mut replay = Replay::with_timing(&page, ms(10), ms(200));
+        let line = page.get(line).unwrap();
+        let rectangle = page.get(rectangle).unwrap();
+
+        assert_eq!(
+            replay.advance(ms(100)),
+            vec![DrawEvent::Progress {
+                element: line,
+                points: 2
+            }]
+        );
+        assert_eq!(
+            replay.advance(ms(300)),
+            vec![DrawEvent::Complete(line), DrawEvent::Complete(rectangle)]
+        );
+        assert!(replay.advance(ms(300)).is_empty());
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn speed_scales_wall_clock_time() {
+        let (page, _, _) = page();
+        let mut replay = Replay::with_timing(&page, ms(10), ms(200));
+        replay.set_speed(2.0);
+        replay.advance(ms(100));
+        assert_eq!(replay.position(), ms(200));
+    }
+
+    #[test]
+    fn huge_speeds_finish_playback_without_panicking() {
+        let (page, _, _) = page();
+        let mut replay = Replay::with_timing(&page, ms(10), ms(200));
+        replay.set_speed(f64::INFINITY);
+        assert_eq!(replay.speed(), f64::MAX);
+        assert!(replay.advance(Duration::ZERO).is_empty());
+        assert_eq!(replay.advance(ms(1)).len(), 2);
+        assert!(replay.is_finished());
+
+        replay.seek(ms(0));
+        replay.set_speed(1e300);
+        replay.advance(Duration::MAX);
+        assert!(replay.is_finished());
+
+        replay.seek(ms(0));
+        replay.set_speed(f64::NAN);
+        assert_eq!(replay.speed(), 0.0);
+        replay.advance(ms(100));
+        assert_eq!(replay.position(), ms(0));
+    }
+
+    #[test]
+    fn seek_jumps_without_events() {
+        let (page, line, rectangle) = page();
+        let mut replay = Replay::with_timing(&page, ms(10), ms(200));
+        replay.seek(ms(450));
+        assert_eq!(ids(&replay.frame()), vec![(line, None), (rectangle, None)]);
+        assert!(replay.advance(ms(10)).is_empty());
+
+        replay.seek(ms(0));
+        assert_eq!(replay.position(), ms(0));
+        assert_eq!(replay.advance(ms(400)).len(), 2);
+    }
+
+    #[test]
+    fn extracted_elements_are_erased() {
+        let (mut page, line, _) = page();
+        page.extract_by_id(line);
+        let mut replay = Replay::with_timing(&page, ms(10), ms(200));
+
+        assert_eq!(replay.duration(), ms(600));
+        let events = replay.advance(ms(600));
+        assert!(matches!(events[0], DrawEvent::Complete(element) if element.id() == line));
+        assert!(matches!(events[1], DrawEvent::Erase(element) if element.id() == line));
+        assert!(replay
+            .frame()
+            .iter()
+            .all(|visible| visible.element.id() != line));
+        assert!(replay
+            .frame_at(ms(450))
+            .iter()
+            .any(|visible| visible.element.id() == line));
+    }
+
+    #[test]
+    fn modified_elements_are_redrawn_in_their_new_state() {
+        let (mut page, line, rectangle) = page();
+        page.transform_selection(&[rectangle], &Affine::translate(10.0, 0.0));
+        let replay = Replay::with_timing(&page, ms(10), ms(200));
+
+        assert_eq!(replay.duration(), ms(800));
+        let lower = |time| {
+            replay
+                .frame_at(time)
+                .iter()
+                .filter(|visible| visible.element.id() == rectangle)
+                .map(|visible| visible.element.envelope().lower())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lower(ms(500)), vec![Point::new(0, 0)]);
+        assert_eq!(lower(ms(700)), vec![Point::new(10, 0)]);
+        assert_eq!(
+            ids(&replay.frame_at(ms(700))),
+            vec![(line, None), (rectangle, None)]
+        );
+    }
+}
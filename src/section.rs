@@ -0,0 +1,387 @@
+use std::ops::RangeInclusive;
+
+/// Identifies a section within its journal.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct SectionId(u64);
+
+impl SectionId {
+    pub(crate) fn new(id: u64) -> Self {
+        SectionId(id)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A titled range of pages that may contain nested sections.
+///
+/// Sections are kept consistent by the journal: pages inserted inside a section extend it,
+/// removed pages shrink it, and sections without pages are removed.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Section {
+    id: SectionId,
+    title: String,
+    first: usize,
+    last: usize,
+    children: Vec<Section>,
+}
+
+/// An entry of a table of contents.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TocEntry {
+    pub section: SectionId,
+    pub title: String,
+    /// The nesting level, starting at 0 for top-level sections.
+    pub depth: usize,
+    /// The index of the first page of the section.
+    pub page: usize,
+}
+
+impl Section {
+    pub(crate) fn new(id: SectionId, title: String, pages: RangeInclusive<usize>) -> Self {
+        Section {
+            id,
+            title,
+            first: *pages.start(),
+            last: *pages.end(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> SectionId {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub(crate) fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    /// The indices of the pages covered by the section.
+    pub fn pages(&self) -> RangeInclusive<usize> {
+        self.first..=self.last
+    }
+
+    pub fn children(&self) -> &[Section] {
+        &self.children
+    }
+
+    fn relocate(&mut self, at: usize) {
+        self.first = at;
+        self.last = at;
+        for child in &mut self.children {
+            child.relocate(at);
+        }
+    }
+
+    fn contains(&self, other: &Section) -> bool {
+        self.first <= other.first && other.last <= self.last
+    }
+
+    fn overlaps(&self, other: &Section) -> bool {
+        self.first <= other.last && other.first <= self.last
+    }
+}
+
+/// Adds a section to the deepest section that contains it.
+///
+/// Sections covered by the new section become its children. Returns the section back if it
+/// partially overlaps another section.
+pub(crate) fn insert(sections: &mut Vec<Section>, mut section: Section) -> Result<(), Section> {
+    if let Some(parent) = sections
+        .iter_mut()
+        .find(|existing| existing.contains(&section))
+    {
+        return insert(&mut parent.children, section);
+    }
+    if sections
+        .iter()
+        .any(|existing| existing.overlaps(&section) && !section.contains(existing))
+    {
+        return Err(section);
+    }
+    let (children, siblings): (Vec<Section>, Vec<Section>) = std::mem::take(sections)
+        .into_iter()
+        .partition(|existing| section.contains(existing));
+    section.children.extend(children);
+    section.children.sort_by_key(|child| child.first);
+    *sections = siblings;
+    let position = sections
+        .iter()
+        .position(|existing| existing.first > section.first)
+        .unwrap_or(sections.len());
+    sections.insert(position, section);
+    Ok(())
+}
+
+/// Removes a section and moves its children up to its parent.
+pub(crate) fn remove(sections: &mut Vec<Section>, id: SectionId) -> bool {
+    if let Some(position) = sections.iter().position(|section| section.id == id) {
+        let section = sections.remove(position);
+        for (offset, child) in section.children.into_iter().enumerate() {
+            sections.insert(position + offset, child);
+        }
+        return true;
+    }
+    sections
+        .iter_mut()
+        .any(|section| remove(&mut section.children, id))
+}
+
+pub(crate) fn find_mut(sections: &mut [Section], id: SectionId) -> Option<&mut Section> {
+    for section in sections {
+        if section.id == id {
+            return Some(section);
+        }
+        if let Some(found) = find_mut(&mut section.children, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+pub(crate) fn find(sections: &[Section], id: SectionId) -> Option<&Section> {
+    sections.iter().find_map(|section| {
+        if section.id == id {
+            Some(section)
+        } else {
+            find(&section.children, id)
+        }
+    })
+}
+
+/// Updates the ranges after a page was inserted at `at`.
+///
+/// Pages inserted at the first page of a section are placed before it, pages inserted after
+/// the first page extend the section.
+pub(crate) fn page_inserted(sections: &mut [Section], at: usize) {
+    for section in sections {
+        if at <= section.first {
+            section.first += 1;
+            section.last += 1;
+        } else if at <= section.last {
+            section.last += 1;
+        }
+        page_inserted(&mut section.children, at);
+    }
+}
+
+/// Updates the ranges after the page at `at` was removed and drops sections without pages.
+pub(crate) fn page_removed(sections: &mut Vec<Section>, at: usize) {
+    sections.retain_mut(|section| {
+        if at < section.first {
+            section.first -= 1;
+            section.last -= 1;
+        } else if at <= section.last {
+            if section.first == section.last {
+                return false;
+            }
+            section.last -= 1;
+        }
+        page_removed(&mut section.children, at);
+        true
+    });
+}
+
+/// Updates the ranges after the page at `from` was moved to `to`.
+///
+/// Sections that only cover the moved page follow it. Other sections keep their pages; the
+/// moved page stays in a section it was part of if it lands inside or next to the rest of it,
+/// and joins a section if it lands between two of its pages.
+pub(crate) fn page_moved(sections: &mut Vec<Section>, from: usize, to: usize) {
+    if from == to {
+        return;
+    }
+    let mut moved = Vec::new();
+    detach(sections, from, &mut moved);
+    shift_moved(sections, from, to);
+    for mut section in moved {
+        section.relocate(to);
+        // A single page is either inside, around or outside any other section.
+        let inserted = insert(sections, section);
+        debug_assert!(inserted.is_ok());
+    }
+}
+
+fn shift_moved(sections: &mut [Section], from: usize, to: usize) {
+    // The new index of a page other than the moved one.
+    let shifted = |index: usize| {
+        if from < index && index <= to {
+            index - 1
+        } else if to <= index && index < from {
+            index + 1
+        } else {
+            index
+        }
+    };
+    for section in sections.iter_mut() {
+        let contained = section.pages().contains(&from);
+        let first = shifted(section.first + usize::from(section.first == from));
+        let last = shifted(section.last - usize::from(section.last == from));
+        (section.first, section.last) = if contained && to + 1 == first {
+            (to, last)
+        } else if contained && to == last + 1 {
+            (first, to)
+        } else {
+            (first, last)
+        };
+        shift_moved(&mut section.children, from, to);
+    }
+    sections.sort_by_key(|section| section.first);
+}
+
+fn detach(sections: &mut Vec<Section>, at: usize, detached: &mut Vec<Section>) {
+    let (single, rest): (Vec<Section>, Vec<Section>) = std::mem::take(sections)
+        .into_iter()
+        .partition(|section| section.pages() == (at..=at));
+    detached.extend(single);
+    *sections = rest;
+    for section in sections {
+        detach(&mut section.children, at, detached);
+    }
+}
+
+pub(crate) fn table_of_contents(sections: &[Section], depth: usize, entries: &mut Vec<TocEntry>) {
+    for section in sections {
+        entries.push(TocEntry {
+            section: section.id,
+            title: section.title.clone(),
+            depth,
+            page: section.first,
+        });
+        table_of_contents(&section.children, depth + 1, entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: u64, pages: RangeInclusive<usize>) -> Section {
+        Section::new(SectionId::new(id), format!("{}", id), pages)
+    }
+
+    fn ranges(sections: &[Section]) -> Vec<(u64, RangeInclusive<usize>, usize)> {
+        let mut entries = Vec::new();
+        table_of_contents(sections, 0, &mut entries);
+        entries
+            .iter()
+            .map(|entry| {
+                let section = find(sections, entry.section).unwrap();
+                (entry.section.value(), section.pages(), entry.depth)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nests_sections_automatically() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=3)).unwrap();
+        insert(&mut sections, section(2, 5..=6)).unwrap();
+        insert(&mut sections, section(3, 0..=9)).unwrap();
+        insert(&mut sections, section(4, 5..=5)).unwrap();
+        assert_eq!(
+            ranges(&sections),
+            vec![(3, 0..=9, 0), (1, 2..=3, 1), (2, 5..=6, 1), (4, 5..=5, 2)]
+        );
+    }
+
+    #[test]
+    fn rejects_partial_overlaps() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=5)).unwrap();
+        assert!(insert(&mut sections, section(2, 4..=7)).is_err());
+        assert_eq!(sections.len(), 1);
+    }
+
+    #[test]
+    fn removing_a_section_promotes_its_children() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 0..=9)).unwrap();
+        insert(&mut sections, section(2, 0..=1)).unwrap();
+        insert(&mut sections, section(3, 2..=3)).unwrap();
+        assert!(remove(&mut sections, SectionId::new(1)));
+        assert!(!remove(&mut sections, SectionId::new(1)));
+        assert_eq!(ranges(&sections), vec![(2, 0..=1, 0), (3, 2..=3, 0)]);
+    }
+
+    #[test]
+    fn inserted_pages_shift_or_extend_sections() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=4)).unwrap();
+        page_inserted(&mut sections, 2);
+        assert_eq!(sections[0].pages(), 3..=5);
+        page_inserted(&mut sections, 5);
+        assert_eq!(sections[0].pages(), 3..=6);
+        page_inserted(&mut sections, 7);
+        assert_eq!(sections[0].pages(), 3..=6);
+    }
+
+    #[test]
+    fn removed_pages_shrink_or_drop_sections() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=4)).unwrap();
+        insert(&mut sections, section(2, 4..=4)).unwrap();
+        page_removed(&mut sections, 0);
+        assert_eq!(ranges(&sections), vec![(1, 1..=3, 0), (2, 3..=3, 1)]);
+        page_removed(&mut sections, 3);
+        assert_eq!(ranges(&sections), vec![(1, 1..=2, 0)]);
+    }
+
+    #[test]
+    fn moving_a_page_to_its_own_position_changes_nothing() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=4)).unwrap();
+        page_moved(&mut sections, 4, 4);
+        page_moved(&mut sections, 2, 2);
+        assert_eq!(sections[0].pages(), 2..=4);
+    }
+
+    #[test]
+    fn first_and_last_pages_stay_when_moved_within_or_next_to_the_section() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=4)).unwrap();
+        page_moved(&mut sections, 2, 4);
+        assert_eq!(sections[0].pages(), 2..=4);
+        page_moved(&mut sections, 4, 2);
+        assert_eq!(sections[0].pages(), 2..=4);
+        page_moved(&mut sections, 4, 5);
+        assert_eq!(sections[0].pages(), 2..=3);
+        page_moved(&mut sections, 2, 1);
+        assert_eq!(sections[0].pages(), 3..=3);
+    }
+
+    #[test]
+    fn pages_moved_past_a_section_shift_it() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 2..=4)).unwrap();
+        page_moved(&mut sections, 0, 5);
+        assert_eq!(sections[0].pages(), 1..=3);
+        page_moved(&mut sections, 6, 0);
+        assert_eq!(sections[0].pages(), 2..=4);
+        page_moved(&mut sections, 6, 3);
+        assert_eq!(sections[0].pages(), 2..=5);
+    }
+
+    #[test]
+    fn single_page_sections_follow_moved_pages() {
+        let mut sections = Vec::new();
+        insert(&mut sections, section(1, 0..=2)).unwrap();
+        insert(&mut sections, section(2, 1..=1)).unwrap();
+        insert(&mut sections, section(3, 1..=1)).unwrap();
+        page_moved(&mut sections, 1, 4);
+        assert_eq!(
+            ranges(&sections),
+            vec![(1, 0..=1, 0), (2, 4..=4, 0), (3, 4..=4, 1)]
+        );
+        page_moved(&mut sections, 4, 0);
+        assert_eq!(
+            ranges(&sections),
+            vec![(2, 0..=0, 0), (3, 0..=0, 1), (1, 1..=2, 0)]
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod recognize;
+pub mod simplify;
+pub mod smooth;
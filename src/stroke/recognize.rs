@@ -0,0 +1,400 @@
+use std::cmp::Ordering;
+
+use rstar::AABB;
+
+use crate::stroke::simplify::distance_to_segment;
+use crate::{Arrow, Element, Ellipse, Line, Point, Polygon, Rectangle, Simplification};
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Segment(Line),
+    Rectangle(Rectangle),
+    Ellipse(Ellipse),
+    Triangle(Polygon),
+    Arrow(Arrow),
+}
+
+impl Shape {
+    pub fn into_element(self) -> Box<dyn Element> {
+        match self {
+            Shape::Segment(line) => Box::new(line),
+            Shape::Rectangle(rectangle) => Box::new(rectangle),
+            Shape::Ellipse(ellipse) => Box::new(ellipse),
+            Shape::Triangle(triangle) => Box::new(triangle),
+            Shape::Arrow(arrow) => Box::new(arrow),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    shape: Shape,
+    confidence: f64,
+}
+
+impl Suggestion {
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn into_shape(self) -> Shape {
+        self.shape
+    }
+
+    /// A value between 0 and 1, where 1 means the stroke matches the shape exactly.
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+}
+
+/// Suggests geometric shapes for hand drawn strokes.
+///
+/// Apply a suggestion with `Page::replace_with_shape`, which keeps the `z_index` and `t_index`
+/// of the stroke.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Recognizer {
+    threshold: f64,
+}
+
+impl Recognizer {
+    pub fn new(threshold: f64) -> Self {
+        Recognizer { threshold }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the most likely shape if its confidence reaches the threshold.
+    pub fn recognize(&self, line: &Line) -> Option<Suggestion> {
+        self.suggestions(line)
+            .into_iter()
+            .next()
+            .filter(|suggestion| suggestion.confidence >= self.threshold)
+    }
+
+    /// Returns all candidate shapes, most likely first.
+    pub fn suggestions(&self, line: &Line) -> Vec<Suggestion> {
+        let points: Vec<Point> = line.iter().copied().collect();
+        if points.len() < 2 {
+            return Vec::new();
+        }
+        let stroke = Stroke::new(points);
+        if stroke.diagonal == 0.0 {
+            return Vec::new();
+        }
+
+        let mut suggestions: Vec<Suggestion> = if stroke.is_closed() {
+            [stroke.ellipse(), stroke.rectangle(), stroke.triangle()]
+                .into_iter()
+                .flatten()
+                .collect()
+        } else {
+            [stroke.segment(), stroke.arrow()]
+                .into_iter()
+                .flatten()
+                .collect()
+        };
+        suggestions.retain(|suggestion| suggestion.confidence > 0.0);
+        suggestions.sort_by(|lhs, rhs| {
+            rhs.confidence
+                .partial_cmp(&lhs.confidence)
+                .unwrap_or(Ordering::Equal)
+        });
+        suggestions
+    }
+}
+
+impl Default for Recognizer {
+    fn default() -> Self {
+        Recognizer::new(0.75)
+    }
+}
+
+struct Stroke {
+    points: Vec<Point>,
+    envelope: AABB<Point>,
+    diagonal: f64,
+}
+
+fn confidence(error: f64, tolerance: f64) -> f64 {
+    (1.0 - error / tolerance).clamp(0.0, 1.0)
+}
+
+fn suggestion(shape: Shape, confidence: f64) -> Option<Suggestion> {
+    Some(Suggestion { shape, confidence })
+}
+
+impl Stroke {
+    fn new(points: Vec<Point>) -> Self {
+        let envelope = AABB::from_points(points.iter());
+        let diagonal = envelope.lower().distance(envelope.upper());
+        Stroke {
+            points,
+            envelope,
+            diagonal,
+        }
+    }
+
+    fn first(&self) -> Point {
+        self.points[0]
+    }
+
+    fn last(&self) -> Point {
+        self.points[self.points.len() - 1]
+    }
+
+    fn is_closed(&self) -> bool {
+        self.first().distance(self.last()) <= 0.2 * self.diagonal
+    }
+
+    /// Indices of the points that remain after coarse simplification.
+    fn corners(&self) -> Vec<usize> {
+        Simplification::RamerDouglasPeucker(0.1 * self.diagonal)
+            .retained(&self.points)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, keep)| keep.then_some(index))
+            .collect()
+    }
+
+    fn mean_distance_to_outline(&self, outline: &[Point]) -> f64 {
+        let total: f64 = self
+            .points
+            .iter()
+            .map(|point| {
+                (0..outline.len())
+                    .map(|i| {
+                        distance_to_segment(*point, outline[i], outline[(i + 1) % outline.len()])
+                    })
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .sum();
+        total / self.points.len() as f64
+    }
+
+    fn segment(&self) -> Option<Suggestion> {
+        let length = self.first().distance(self.last());
+        let deviation = self
+            .points
+            .iter()
+            .map(|point| distance_to_segment(*point, self.first(), self.last()))
+            .fold(0.0, f64::max);
+        suggestion(
+            Shape::Segment(Line::from_iter([self.first(), self.last()])),
+            confidence(deviation, 0.1 * length),
+        )
+    }
+
+    fn rectangle(&self) -> Option<Suggestion> {
+        let lower = self.envelope.lower();
+        let upper = self.envelope.upper();
+        let outline = [
+            lower,
+            Point {
+                x: upper.x,
+                y: lower.y,
+            },
+            upper,
+            Point {
+                x: lower.x,
+                y: upper.y,
+            },
+        ];
+        suggestion(
+            Shape::Rectangle(Rectangle::new(lower, upper)),
+            confidence(
+                self.mean_distance_to_outline(&outline),
+                0.05 * self.diagonal,
+            ),
+        )
+    }
+
+    fn ellipse(&self) -> Option<Suggestion> {
+        let radius = (self.envelope.upper() - self.envelope.lower()) / 2;
+        let ellipse = Ellipse::new(self.envelope.lower() + radius, radius.x, radius.y);
+        if ellipse.radius_x() == 0 || ellipse.radius_y() == 0 {
+            return None;
+        }
+        let center = ellipse.center();
+        let error: f64 = self
+            .points
+            .iter()
+            .map(|point| {
+                let offset = *point - center;
+                let x = offset.x as f64 / ellipse.radius_x() as f64;
+                let y = offset.y as f64 / ellipse.radius_y() as f64;
+                (x.hypot(y) - 1.0).abs()
+            })
+            .sum::<f64>()
+            / self.points.len() as f64;
+        suggestion(Shape::Ellipse(ellipse), confidence(error, 0.15))
+    }
+
+    fn triangle(&self) -> Option<Suggestion> {
+        let mut corners: Vec<Point> = self.corners().into_iter().map(|i| self.points[i]).collect();
+        if corners.len() > 1 && corners[0].distance(self.last()) <= 0.2 * self.diagonal {
+            corners.pop();
+        }
+        if corners.len() != 3 {
+            return None;
+        }
+        let error = self.mean_distance_to_outline(&corners);
+        suggestion(
+            Shape::Triangle(Polygon::from_iter(corners)),
+            confidence(error, 0.05 * self.diagonal),
+        )
+    }
+
+    /// Recognizes arrows drawn in one stroke: the shaft from tail to head, followed by the barbs.
+    fn arrow(&self) -> Option<Suggestion> {
+        let corners = self.corners();
+        if corners.len() < 3 {
+            return None;
+        }
+        let tail = self.points[0];
+        let head_index = corners[1];
+        let head = self.points[head_index];
+        let shaft = tail.distance(head);
+        if shaft < 0.5 * self.diagonal {
+            return None;
+        }
+
+        let deviation = self.points[..=head_index]
+            .iter()
+            .map(|point| distance_to_segment(*point, tail, head))
+            .fold(0.0, f64::max);
+        let shaft_angle = (tail - head).angle();
+        let barbs: Vec<Point> = corners[2..]
+            .iter()
+            .map(|i| self.points[*i])
+            .filter(|point| point.distance(head) > 0.1 * shaft)
+            .collect();
+        if barbs.is_empty() {
+            return None;
+        }
+        let valid = barbs
+            .iter()
+            .filter(|barb| {
+                let angle = (**barb - head).angle();
+                let difference = (angle - shaft_angle).sin().abs().asin().to_degrees();
+                barb.distance(head) <= 0.5 * shaft && (10.0..=80.0).contains(&difference)
+            })
+            .count();
+        suggestion(
+            Shape::Arrow(Arrow::new(tail, head)),
+            confidence(deviation, 0.1 * shaft) * valid as f64 / barbs.len() as f64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    fn recognize(points: &[(i32, i32)]) -> Suggestion {
+        Recognizer::default()
+            .recognize(&Line::from_iter(points.iter()))
+            .unwrap()
+    }
+
+    #[test]
+    fn recognizes_segment() {
+        let suggestion = recognize(&[(0, 0), (25, 1), (50, -1), (75, 1), (100, 0)]);
+        match suggestion.shape() {
+            Shape::Segment(line) => assert!(line.iter().eq([(0, 0), (100, 0)].iter())),
+            other => panic!("unexpected shape {:?}", other),
+        }
+        assert!(suggestion.confidence() > 0.75);
+    }
+
+    #[test]
+    fn recognizes_rectangle() {
+        let suggestion = recognize(&[
+            (0, 0),
+            (50, 1),
+            (100, 0),
+            (99, 30),
+            (100, 60),
+            (50, 59),
+            (0, 60),
+            (1, 30),
+            (2, 2),
+        ]);
+        match suggestion.shape() {
+            Shape::Rectangle(rectangle) => {
+                assert_eq!(rectangle.lower(), (0, 0));
+                assert_eq!(rectangle.upper(), (100, 60));
+            }
+            other => panic!("unexpected shape {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_ellipse() {
+        let points: Vec<Point> = (0..=32)
+            .map(|i| {
+                let angle = TAU * i as f64 / 32.0;
+                Point::new(
+                    (100.0 * angle.cos()).round() as i128,
+                    (50.0 * angle.sin()).round() as i128,
+                )
+            })
+            .collect();
+        let suggestion = Recognizer::default()
+            .recognize(&Line::from_iter(points))
+            .unwrap();
+        match suggestion.shape() {
+            Shape::Ellipse(ellipse) => {
+                assert_eq!(ellipse.center(), (0, 0));
+                assert_eq!(ellipse.radius_x(), 100);
+                assert_eq!(ellipse.radius_y(), 50);
+            }
+            other => panic!("unexpected shape {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_triangle() {
+        let suggestion = recognize(&[
+            (0, 0),
+            (50, 0),
+            (100, 0),
+            (75, 43),
+            (50, 87),
+            (25, 43),
+            (1, 1),
+        ]);
+        match suggestion.shape() {
+            Shape::Triangle(triangle) => {
+                assert!(triangle.iter().eq([(0, 0), (100, 0), (50, 87)].iter()))
+            }
+            other => panic!("unexpected shape {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recognizes_arrow() {
+        let suggestion = recognize(&[(0, 0), (50, 0), (100, 0), (80, 15), (100, 0), (80, -15)]);
+        match suggestion.shape() {
+            Shape::Arrow(arrow) => {
+                assert_eq!(arrow.tail(), (0, 0));
+                assert_eq!(arrow.head(), (100, 0));
+            }
+            other => panic!("unexpected shape {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_scribbles() {
+        let line = Line::from_iter([(0, 0), (40, 90), (60, 5), (100, 70), (10, 40)].iter());
+        assert!(Recognizer::default().recognize(&line).is_none());
+    }
+
+    #[test]
+    fn suggests_nothing_for_single_points() {
+        let line = Line::from_iter([(3, 3), (3, 3)].iter());
+        assert!(Recognizer::default().suggestions(&line).is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Point;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Simplification {
+    /// Ramer–Douglas–Peucker: drops points closer than the tolerance to the simplified stroke.
+    RamerDouglasPeucker(f64),
+    /// Visvalingam–Whyatt: drops points whose triangle with their neighbours has an area below
+    /// the square of the tolerance.
+    Visvalingam(f64),
+}
+
+impl Simplification {
+    pub fn apply(&self, points: &[Point]) -> Vec<Point> {
+        points
+            .iter()
+            .zip(self.retained(points))
+            .filter_map(|(point, keep)| keep.then_some(*point))
+            .collect()
+    }
+
+    /// Flags the points that survive the simplification.
+    pub(crate) fn retained(&self, points: &[Point]) -> Vec<bool> {
+        match self {
+            Simplification::RamerDouglasPeucker(tolerance) => {
+                ramer_douglas_peucker(points, *tolerance)
+            }
+            Simplification::Visvalingam(tolerance) => visvalingam(points, *tolerance),
+        }
+    }
+}
+
+pub(crate) fn distance_to_segment(point: Point, start: Point, end: Point) -> f64 {
+    let segment = end - start;
+    let offset = point - start;
+    let projection = offset.dot(segment);
+    if projection <= 0 {
+        return point.distance(start);
+    }
+    if projection >= segment.length_squared() {
+        return point.distance(end);
+    }
+    offset.cross(segment).abs() as f64 / segment.length()
+}
+
+fn triangle_area(a: Point, b: Point, c: Point) -> f64 {
+    (b - a).cross(c - a).abs() as f64 / 2.0
+}
+
+fn ramer_douglas_peucker(points: &[Point], tolerance: f64) -> Vec<bool> {
+    if points.len() < 3 {
+        return vec![true; points.len()];
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| {
+                (
+                    i,
+                    distance_to_segment(points[i], points[first], points[last]),
+                )
+            })
+            .max_by(|lhs, rhs| lhs.1.total_cmp(&rhs.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                ranges.push((first, index));
+                ranges.push((index, last));
+            }
+        }
+    }
+    keep
+}
+
+fn visvalingam(points: &[Point], tolerance: f64) -> Vec<bool> {
+    if points.len() < 3 {
+        return vec![true; points.len()];
+    }
+    let threshold = tolerance * tolerance;
+    let last = points.len() - 1;
+    let mut previous: Vec<usize> = (0..points.len()).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..points.len()).map(|i| (i + 1).min(last)).collect();
+    let mut removed = vec![false; points.len()];
+    let area = |i: usize, previous: &[usize], next: &[usize]| {
+        triangle_area(points[previous[i]], points[i], points[next[i]])
+    };
+
+    // Areas are non-negative, so their bit patterns order like the values themselves.
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = (1..last)
+        .map(|i| Reverse((area(i, &previous, &next).to_bits(), i)))
+        .collect();
+    while let Some(Reverse((bits, i))) = heap.pop() {
+        if removed[i] || bits != area(i, &previous, &next).to_bits() {
+            continue;
+        }
+        if f64::from_bits(bits) >= threshold {
+            break;
+        }
+        removed[i] = true;
+        let (before, after) = (previous[i], next[i]);
+        next[before] = after;
+        previous[after] = before;
+        for neighbour in [before, after] {
+            if neighbour != 0 && neighbour != last {
+                heap.push(Reverse((
+                    area(neighbour, &previous, &next).to_bits(),
+                    neighbour,
+                )));
+            }
+        }
+    }
+    removed.into_iter().map(|removed| !removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coordinates: &[(i32, i32)]) -> Vec<Point> {
+        coordinates.iter().map(Point::from).collect()
+    }
+
+    #[test]
+    fn distance_to_segment_is_clamped_to_endpoints() {
+        let start = Point::new(0, 0);
+        let end = Point::new(10, 0);
+        assert_eq!(distance_to_segment((5, 3).into(), start, end), 3.0);
+        assert_eq!(distance_to_segment((13, 4).into(), start, end), 5.0);
+        assert_eq!(distance_to_segment((3, 4).into(), start, start), 5.0);
+    }
+
+    #[test]
+    fn ramer_douglas_peucker_drops_collinear_points() {
+        let stroke = points(&[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]);
+        assert_eq!(
+            Simplification::RamerDouglasPeucker(0.5).apply(&stroke),
+            points(&[(0, 0), (4, 0)])
+        );
+    }
+
+    #[test]
+    fn ramer_douglas_peucker_keeps_corners() {
+        let stroke = points(&[(0, 0), (5, 1), (10, 0), (10, 5), (10, 10)]);
+        assert_eq!(
+            Simplification::RamerDouglasPeucker(2.0).apply(&stroke),
+            points(&[(0, 0), (10, 0), (10, 10)])
+        );
+    }
+
+    #[test]
+    fn visvalingam_drops_small_triangles() {
+        let stroke = points(&[(0, 0), (5, 1), (10, 0), (10, 5), (10, 10)]);
+        assert_eq!(
+            Simplification::Visvalingam(4.0).apply(&stroke),
+            points(&[(0, 0), (10, 0), (10, 10)])
+        );
+    }
+
+    #[test]
+    fn visvalingam_keeps_large_triangles() {
+        let stroke = points(&[(0, 0), (5, 5), (10, 0)]);
+        assert_eq!(Simplification::Visvalingam(4.0).apply(&stroke), stroke);
+    }
+
+    #[test]
+    fn short_strokes_are_unchanged() {
+        let stroke = points(&[(0, 0), (1, 1)]);
+        assert_eq!(
+            Simplification::RamerDouglasPeucker(10.0).apply(&stroke),
+            stroke
+        );
+        assert_eq!(Simplification::Visvalingam(10.0).apply(&stroke), stroke);
+    }
+}
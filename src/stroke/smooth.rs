@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use crate::FloatPoint;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Smoothing {
+    /// Chaikin corner cutting, applied the given number of times.
+    Chaikin(usize),
+    /// A Catmull-Rom spline through the points with the given number of segments per span.
+    CatmullRom(usize),
+    /// Averages each position with the given number of neighbours on either side. Keeps the
+    /// number of points and their pressure.
+    MovingAverage(usize),
+}
+
+/// A point of a stroke together with its pressure and timestamp.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) struct Sample {
+    pub x: f64,
+    pub y: f64,
+    pub pressure: f64,
+    pub time: f64,
+}
+
+impl Sample {
+    pub fn from_float(point: FloatPoint, pressure: u16) -> Self {
+        Sample {
+            x: point.x,
+            y: point.y,
+            pressure: pressure as f64,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_time(self, time: Duration) -> Self {
+        Sample {
+            time: time.as_secs_f64(),
+            ..self
+        }
+    }
+
+    pub fn float_point(&self) -> FloatPoint {
+        FloatPoint::new(self.x, self.y)
+    }
+
+    pub fn pressure(&self) -> u16 {
+        self.pressure.round().clamp(0.0, u16::MAX as f64) as u16
+    }
+
+    pub fn time(&self) -> Duration {
+        Duration::from_secs_f64(self.time.max(0.0))
+    }
+
+    fn lerp(&self, other: &Sample, t: f64) -> Sample {
+        Sample {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            pressure: self.pressure + (other.pressure - self.pressure) * t,
+            time: self.time + (other.time - self.time) * t,
+        }
+    }
+
+    fn distance(&self, other: &Sample) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+impl Smoothing {
+    pub(crate) fn apply(&self, samples: &[Sample]) -> Vec<Sample> {
+        if samples.len() < 3 {
+            return samples.to_vec();
+        }
+        match self {
+            Smoothing::Chaikin(iterations) => chaikin(samples, *iterations),
+            Smoothing::CatmullRom(segments) => catmull_rom(samples, *segments),
+            Smoothing::MovingAverage(radius) => moving_average(samples, *radius),
+        }
+    }
+}
+
+fn chaikin(samples: &[Sample], iterations: usize) -> Vec<Sample> {
+    let mut current = samples.to_vec();
+    for _ in 0..iterations {
+        let mut next = Vec::with_capacity(current.len() * 2);
+        next.push(current[0]);
+        for pair in current.windows(2) {
+            next.push(pair[0].lerp(&pair[1], 0.25));
+            next.push(pair[0].lerp(&pair[1], 0.75));
+        }
+        next.push(current[current.len() - 1]);
+        current = next;
+    }
+    current
+}
+
+fn catmull_rom(samples: &[Sample], segments: usize) -> Vec<Sample> {
+    let segments = segments.max(1);
+    let last = samples.len() - 1;
+    let mut result = Vec::with_capacity(last * segments + 1);
+    for i in 0..last {
+        let p0 = samples[i.saturating_sub(1)];
+        let p1 = samples[i];
+        let p2 = samples[i + 1];
+        let p3 = samples[(i + 2).min(last)];
+        for step in 0..segments {
+            let t = step as f64 / segments as f64;
+            let spline = |v0: f64, v1: f64, v2: f64, v3: f64| {
+                0.5 * (2.0 * v1
+                    + (v2 - v0) * t
+                    + (2.0 * v0 - 5.0 * v1 + 4.0 * v2 - v3) * t * t
+                    + (3.0 * v1 - v0 - 3.0 * v2 + v3) * t * t * t)
+            };
+            result.push(Sample {
+                x: spline(p0.x, p1.x, p2.x, p3.x),
+                y: spline(p0.y, p1.y, p2.y, p3.y),
+                pressure: p1.pressure + (p2.pressure - p1.pressure) * t,
+                time: p1.time + (p2.time - p1.time) * t,
+            });
+        }
+    }
+    result.push(samples[last]);
+    result
+}
+
+fn moving_average(samples: &[Sample], radius: usize) -> Vec<Sample> {
+    let last = samples.len() - 1;
+    (0..samples.len())
+        .map(|i| {
+            // Shrink the window near the ends so that the endpoints stay in place.
+            let radius = radius.min(i).min(last - i);
+            let window = &samples[i - radius..=i + radius];
+            let count = window.len() as f64;
+            Sample {
+                x: window.iter().map(|sample| sample.x).sum::<f64>() / count,
+                y: window.iter().map(|sample| sample.y).sum::<f64>() / count,
+                pressure: samples[i].pressure,
+                time: samples[i].time,
+            }
+        })
+        .collect()
+}
+
+/// Places samples at a fixed arc length distance along the stroke, keeping both endpoints.
+pub(crate) fn resample(samples: &[Sample], spacing: f64) -> Vec<Sample> {
+    if samples.len() < 2 || spacing <= 0.0 {
+        return samples.to_vec();
+    }
+    let mut result = vec![samples[0]];
+    let mut travelled = 0.0;
+    let mut next = spacing;
+    for pair in samples.windows(2) {
+        let length = pair[0].distance(&pair[1]);
+        while length > 0.0 && travelled + length >= next {
+            result.push(pair[0].lerp(&pair[1], (next - travelled) / length));
+            next += spacing;
+        }
+        travelled += length;
+    }
+    let last = samples[samples.len() - 1];
+    if result[result.len() - 1].distance(&last) > spacing / 2.0 || result.len() == 1 {
+        result.push(last);
+    } else {
+        let index = result.len() - 1;
+        result[index] = last;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn samples(coordinates: &[(i32, i32, u16)]) -> Vec<Sample> {
+        coordinates
+            .iter()
+            .map(|(x, y, pressure)| Sample::from_float((*x as f64, *y as f64).into(), *pressure))
+            .collect()
+    }
+
+    fn points(samples: &[Sample]) -> Vec<Point> {
+        samples
+            .iter()
+            .map(|sample| sample.float_point().round())
+            .collect()
+    }
+
+    #[test]
+    fn chaikin_cuts_corners() {
+        let stroke = samples(&[(0, 0, 0), (40, 0, 0), (40, 40, 0)]);
+        let smoothed = Smoothing::Chaikin(1).apply(&stroke);
+        assert_eq!(
+            points(&smoothed),
+            [(0, 0), (10, 0), (30, 0), (40, 10), (40, 30), (40, 40)].map(Point::from)
+        );
+    }
+
+    #[test]
+    fn catmull_rom_interpolates_points() {
+        let stroke = samples(&[(0, 0, 0), (10, 10, 100), (20, 0, 200)]);
+        let smoothed = Smoothing::CatmullRom(4).apply(&stroke);
+        assert_eq!(smoothed.len(), 9);
+        assert_eq!(smoothed[0].float_point().round(), (0, 0));
+        assert_eq!(smoothed[4].float_point().round(), (10, 10));
+        assert_eq!(smoothed[8].float_point().round(), (20, 0));
+        assert_eq!(smoothed[2].pressure(), 50);
+    }
+
+    #[test]
+    fn moving_average_preserves_pressure_and_endpoints() {
+        let stroke = samples(&[(0, 0, 1), (10, 6, 2), (20, 0, 3), (30, 6, 4), (40, 0, 5)]);
+        let smoothed = Smoothing::MovingAverage(1).apply(&stroke);
+        assert_eq!(
+            points(&smoothed),
+            [(0, 0), (10, 2), (20, 4), (30, 2), (40, 0)].map(Point::from)
+        );
+        assert!(smoothed
+            .iter()
+            .map(Sample::pressure)
+            .eq([1, 2, 3, 4, 5].into_iter()));
+    }
+
+    #[test]
+    fn resample_places_points_at_fixed_spacing() {
+        let stroke = samples(&[(0, 0, 0), (10, 0, 100), (10, 10, 200)]);
+        let resampled = resample(&stroke, 4.0);
+        assert_eq!(
+            points(&resampled),
+            [(0, 0), (4, 0), (8, 0), (10, 2), (10, 6), (10, 10)].map(Point::from)
+        );
+        assert_eq!(resampled[1].pressure(), 40);
+    }
+
+    #[test]
+    fn resample_interpolates_time() {
+        let stroke = vec![
+            Sample::from_float((0.0, 0.0).into(), 0).with_time(Duration::ZERO),
+            Sample::from_float((10.0, 0.0).into(), 0).with_time(Duration::from_millis(100)),
+        ];
+        let resampled = resample(&stroke, 5.0);
+        assert_eq!(resampled[1].time(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn resample_keeps_short_strokes() {
+        let stroke = samples(&[(0, 0, 0), (1, 0, 0)]);
+        assert_eq!(
+            points(&resample(&stroke, 4.0)),
+            [(0, 0), (1, 0)].map(Point::from)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::Point;
+
+/// The number of model units per millimetre; model coordinates are micrometres.
+pub const MODEL_UNITS_PER_MILLIMETRE: i128 = 1000;
+
+const MILLIMETRES_PER_INCH: f64 = 25.4;
+const POINTS_PER_INCH: f64 = 72.0;
+
+/// A physical unit of length.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Unit {
+    Millimetre,
+    /// A typographic point, 1/72 of an inch.
+    Point,
+    Inch,
+}
+
+impl Unit {
+    fn millimetres(&self) -> f64 {
+        match self {
+            Unit::Millimetre => 1.0,
+            Unit::Point => MILLIMETRES_PER_INCH / POINTS_PER_INCH,
+            Unit::Inch => MILLIMETRES_PER_INCH,
+        }
+    }
+
+    /// A length of `value` in this unit.
+    pub fn length(&self, value: f64) -> Length {
+        Length::millimetres(value * self.millimetres())
+    }
+}
+
+/// The resolution of a device in pixels per inch.
+#[derive(PartialEq, PartialOrd, Debug, Clone, Copy)]
+pub struct Dpi(f64);
+
+impl Dpi {
+    pub fn new(dpi: f64) -> Self {
+        Dpi(dpi)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// A length in model units.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Default, Clone, Copy, Hash)]
+pub struct Length(i128);
+
+impl Length {
+    pub fn new(model_units: i128) -> Self {
+        Length(model_units)
+    }
+
+    pub fn millimetres(millimetres: f64) -> Self {
+        Length((millimetres * MODEL_UNITS_PER_MILLIMETRE as f64).round() as i128)
+    }
+
+    pub fn points(points: f64) -> Self {
+        Unit::Point.length(points)
+    }
+
+    pub fn inches(inches: f64) -> Self {
+        Unit::Inch.length(inches)
+    }
+
+    pub fn pixels(pixels: f64, dpi: Dpi) -> Self {
+        Self::inches(pixels / dpi.value())
+    }
+
+    /// The length in model units.
+    pub fn value(&self) -> i128 {
+        self.0
+    }
+
+    pub fn to_millimetres(&self) -> f64 {
+        self.0 as f64 / MODEL_UNITS_PER_MILLIMETRE as f64
+    }
+
+    pub fn to_points(&self) -> f64 {
+        self.to(Unit::Point)
+    }
+
+    pub fn to_inches(&self) -> f64 {
+        self.to(Unit::Inch)
+    }
+
+    pub fn to_pixels(&self, dpi: Dpi) -> f64 {
+        self.to_inches() * dpi.value()
+    }
+
+    pub fn to(&self, unit: Unit) -> f64 {
+        self.to_millimetres() / unit.millimetres()
+    }
+}
+
+impl Point {
+    /// Converts a position on a device with the given resolution to model coordinates.
+    pub fn from_pixels(x: f64, y: f64, dpi: Dpi) -> Self {
+        Point::new(
+            Length::pixels(x, dpi).value(),
+            Length::pixels(y, dpi).value(),
+        )
+    }
+
+    /// Converts the point to a position on a device with the given resolution.
+    pub fn to_pixels(&self, dpi: Dpi) -> (f64, f64) {
+        (
+            Length::new(self.x).to_pixels(dpi),
+            Length::new(self.y).to_pixels(dpi),
+        )
+    }
+
+    /// Converts the point to the given unit.
+    pub fn to_unit(&self, unit: Unit) -> (f64, f64) {
+        (Length::new(self.x).to(unit), Length::new(self.y).to(unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_unit_is_micrometre() {
+        assert_eq!(Length::millimetres(1.0).value(), 1000);
+        assert_eq!(Length::inches(1.0).value(), 25400);
+        assert_eq!(Length::points(72.0).value(), 25400);
+        assert_eq!(Unit::Millimetre.length(2.5), Length::new(2500));
+    }
+
+    #[test]
+    fn converts_lengths_between_units() {
+        let length = Length::inches(0.5);
+        assert_eq!(length.to_millimetres(), 12.7);
+        assert_eq!(length.to_inches(), 0.5);
+        assert!((length.to_points() - 36.0).abs() < 1e-9);
+        assert_eq!(length.to(Unit::Millimetre), 12.7);
+    }
+
+    #[test]
+    fn converts_pixels_at_dpi() {
+        let dpi = Dpi::new(300.0);
+        assert_eq!(Length::pixels(300.0, dpi), Length::inches(1.0));
+        assert_eq!(Length::millimetres(25.4).to_pixels(dpi), 300.0);
+    }
+
+    #[test]
+    fn strokes_keep_physical_size_across_devices() {
+        let point = Point::from_pixels(96.0, 48.0, Dpi::new(96.0));
+        assert_eq!(point, (25400, 12700));
+        assert_eq!(point.to_pixels(Dpi::new(300.0)), (300.0, 150.0));
+        assert_eq!(point.to_unit(Unit::Millimetre), (25.4, 12.7));
+    }
+}
@@ -0,0 +1,164 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A displacement between two points in model units.
+///
+/// Subtracting two points yields a vector, adding a vector to a point moves it.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy, Hash)]
+pub struct Vector {
+    pub x: i128,
+    pub y: i128,
+}
+
+impl Vector {
+    pub fn new(x: i128, y: i128) -> Self {
+        Vector { x, y }
+    }
+
+    pub fn dot(&self, other: Vector) -> i128 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the cross product, positive if `other` is counterclockwise of `self`.
+    pub fn cross(&self, other: Vector) -> i128 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length_squared(&self) -> i128 {
+        self.dot(*self)
+    }
+
+    pub fn length(&self) -> f64 {
+        (self.x as f64).hypot(self.y as f64)
+    }
+
+    /// The counterclockwise angle from the positive x axis in radians, in `[-π, π]`.
+    pub fn angle(&self) -> f64 {
+        (self.y as f64).atan2(self.x as f64)
+    }
+
+    pub fn checked_add(&self, other: Vector) -> Option<Vector> {
+        Some(Vector::new(
+            self.x.checked_add(other.x)?,
+            self.y.checked_add(other.y)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: Vector) -> Option<Vector> {
+        Some(Vector::new(
+            self.x.checked_sub(other.x)?,
+            self.y.checked_sub(other.y)?,
+        ))
+    }
+
+    pub fn checked_mul(&self, factor: i128) -> Option<Vector> {
+        Some(Vector::new(
+            self.x.checked_mul(factor)?,
+            self.y.checked_mul(factor)?,
+        ))
+    }
+
+    pub fn checked_dot(&self, other: Vector) -> Option<i128> {
+        self.x
+            .checked_mul(other.x)?
+            .checked_add(self.y.checked_mul(other.y)?)
+    }
+
+    pub fn checked_cross(&self, other: Vector) -> Option<i128> {
+        self.x
+            .checked_mul(other.y)?
+            .checked_sub(self.y.checked_mul(other.x)?)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i128> for Vector {
+    type Output = Vector;
+
+    fn mul(self, factor: i128) -> Vector {
+        Vector::new(self.x * factor, self.y * factor)
+    }
+}
+
+/// Divides both components, rounding towards zero.
+impl Div<i128> for Vector {
+    type Output = Vector;
+
+    fn div(self, divisor: i128) -> Vector {
+        Vector::new(self.x / divisor, self.y / divisor)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Vector
+where
+    i128: From<T>,
+{
+    fn from(value: (T, T)) -> Self {
+        Vector::new(value.0.into(), value.1.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let lhs = Vector::new(1, 2);
+        let rhs = Vector::new(3, -1);
+        assert_eq!(lhs + rhs, Vector::new(4, 1));
+        assert_eq!(lhs - rhs, Vector::new(-2, 3));
+        assert_eq!(lhs * 3, Vector::new(3, 6));
+        assert_eq!(Vector::new(5, -5) / 2, Vector::new(2, -2));
+        assert_eq!(-lhs, Vector::new(-1, -2));
+    }
+
+    #[test]
+    fn products_and_length() {
+        let lhs = Vector::new(3, 4);
+        assert_eq!(lhs.dot(Vector::new(1, 1)), 7);
+        assert_eq!(Vector::new(1, 0).cross(Vector::new(0, 1)), 1);
+        assert_eq!(lhs.length_squared(), 25);
+        assert_eq!(lhs.length(), 5.0);
+    }
+
+    #[test]
+    fn angle_to_x_axis() {
+        assert_eq!(Vector::new(2, 0).angle(), 0.0);
+        assert_eq!(Vector::new(0, 3).angle(), std::f64::consts::FRAC_PI_2);
+        assert_eq!(Vector::new(-1, 0).angle(), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn checked_operations_detect_overflow() {
+        let huge = Vector::new(i128::MAX, 0);
+        assert_eq!(huge.checked_add(Vector::new(1, 0)), None);
+        assert_eq!(huge.checked_sub(Vector::new(-1, 0)), None);
+        assert_eq!(huge.checked_mul(2), None);
+        assert_eq!(huge.checked_dot(huge), None);
+        assert_eq!(huge.checked_cross(Vector::new(0, 2)), None);
+        assert_eq!(Vector::new(1, 2).checked_dot(Vector::new(3, 4)), Some(11));
+    }
+}